@@ -0,0 +1,89 @@
+//! A command line client for the Stacks debug server.
+//!
+//! Usage: `stacks-debug [--address <address>] [request...]`
+//!
+//! With a request given, sends it, prints the response and exits. Without one,
+//! reads requests from standard input, one per line. See
+//! `stacks::framework::debug_server` for the list of requests.
+
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process::exit;
+
+use stacks::framework::debug_server::{ADDRESS_VARIABLE, DEFAULT_ADDRESS};
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut address = env::var(ADDRESS_VARIABLE)
+        .unwrap_or_else(|_| DEFAULT_ADDRESS.to_owned());
+    if args.first().map(String::as_str) == Some("--address") {
+        if args.len() < 2 {
+            eprintln!("Usage: stacks-debug [--address <address>] [request...]");
+            exit(2);
+        }
+        address = args.remove(1);
+        args.remove(0);
+    }
+
+    if let Err(e) = run(&address, args) {
+        eprintln!("stacks-debug: {}", e);
+        exit(1);
+    }
+}
+
+fn run(address: &str, args: Vec<String>) -> io::Result<()> {
+    let stream = TcpStream::connect(address)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    if !args.is_empty() {
+        let ok = request(&args.join(" "), &mut writer, &mut reader)?;
+        if !ok {
+            exit(1);
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            request(&line, &mut writer, &mut reader)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends one request and prints its response, returning whether it succeeded.
+fn request(
+    line: &str,
+    writer: &mut TcpStream,
+    reader: &mut BufReader<TcpStream>,
+) -> io::Result<bool> {
+    writeln!(writer, "{}", line)?;
+    writer.flush()?;
+
+    let mut status = String::new();
+    if reader.read_line(&mut status)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let ok = status.trim_end() == "ok";
+    if !ok {
+        eprintln!("{}", status.trim_end());
+    }
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if line.trim_end() == "." {
+            break;
+        }
+        stdout.write_all(line.as_bytes())?;
+    }
+    Ok(ok)
+}
@@ -1,3 +1,7 @@
+#[cfg(feature = "debug-server")]
+pub mod debug_server;
+pub mod overlay;
+pub mod profiler;
 pub mod resource;
 pub mod widgets;
 
@@ -10,9 +14,12 @@ use crate::game::{
     Builder, Game, GameError, InputEvent, RunnerError, State, ID,
 };
 use crate::prelude::*;
+use overlay::{
+    Anchor, OverlayHandle, OverlayLayer, OverlayOptions, OverlayRequest,
+};
 use resource::ResourceStack;
 use skia::{Canvas, Size};
-use widgets::{LayoutSize, Widget, Wrap};
+use widgets::{LayoutSize, TreeNode, Widget, WidgetNode, Wrap};
 
 #[derive(Debug)]
 pub enum FrameworkError {
@@ -49,6 +56,10 @@ pub struct Framework<T: Widget + ?Sized> {
     schedule_set_size: bool,
 
     recycled_resource_stack: ResourceStack,
+    overlays: OverlayLayer,
+
+    #[cfg(feature = "debug-server")]
+    debug_server: Option<debug_server::DebugServer>,
 }
 
 impl<T: Widget + 'static> Framework<T> {
@@ -75,20 +86,50 @@ impl<T: Widget + 'static> Framework<T> {
             size: Size::new_empty(),
             schedule_set_size: false,
             recycled_resource_stack: ResourceStack::new(),
+            overlays: OverlayLayer::new(),
+            #[cfg(feature = "debug-server")]
+            debug_server: debug_server::DebugServer::from_env(),
         }
     }
 
     fn maybe_load(&mut self) {
+        self.overlays.adopt(FrameworkState::take_overlay_requests());
         if FrameworkState::consume_load_request() {
             self.root.load(&mut self.recycled_resource_stack);
             assert!(self.recycled_resource_stack.is_empty());
+            self.overlays.load();
+        }
+    }
+
+    /// Passes an event to the overlays, then to the root if none of them
+    /// took it.
+    fn dispatch(&mut self, event: &InputEvent) {
+        if !self.overlays.input(event) {
+            self.root.input(event);
+        } else if let InputEvent::MouseMove(_) = event {
+            self.overlays.remove_hover(&mut self.root);
+        }
+    }
+
+    #[cfg(feature = "debug-server")]
+    fn poll_debug_server(&mut self) {
+        let mut events = vec![];
+        if let Some(server) = &mut self.debug_server {
+            server.poll(&self.root, &mut events);
+        }
+        for event in events {
+            self.input(event);
         }
     }
 }
 
 impl<T: Widget + 'static> Game for Framework<T> {
     fn update(&mut self) {
+        #[cfg(feature = "debug-server")]
+        self.poll_debug_server();
+
         self.root.update();
+        self.overlays.update();
         self.maybe_load();
     }
 
@@ -107,6 +148,13 @@ impl<T: Widget + 'static> Game for Framework<T> {
 
         // Do the actual drawing
         self.root.draw(canvas);
+        self.overlays.set_window_size(self.size);
+        self.overlays.draw(canvas);
+        profiler::Profiler::end_frame();
+        #[cfg(feature = "debug-server")]
+        if let Some(server) = &self.debug_server {
+            server.draw(&self.root, canvas);
+        }
         self.maybe_load();
     }
 
@@ -128,17 +176,17 @@ impl<T: Widget + 'static> Game for Framework<T> {
             None
         });
         if let Some(id) = remove_hover {
-            self.root.input(&InputEvent::RemoveHoverExcept(id));
+            self.dispatch(&InputEvent::RemoveHoverExcept(id));
         }
         if let Some(id) = FrameworkState::current_focus() {
             let focused = InputEvent::Focused(id, Box::new(event.clone()));
-            self.root.input(&focused);
+            self.dispatch(&focused);
             let should_resend = || FrameworkState::consume_reinput_request();
             if should_resend() {
-                self.root.input(&event);
+                self.dispatch(&event);
             }
         } else {
-            self.root.input(&event);
+            self.dispatch(&event);
         }
         self.maybe_load();
     }
@@ -147,6 +195,7 @@ impl<T: Widget + 'static> Game for Framework<T> {
 
     fn crash(&mut self, err: GameError) {
         eprintln!("Stacks has crashed!\nMore info: {:?}", err);
+        eprintln!("Widget tree:\n{}", TreeNode::capture(&self.root).to_text());
     }
 }
 
@@ -156,6 +205,7 @@ pub struct FrameworkState {
     just_grabbed_focus: bool,
     resend_input: bool,
     load_requested: bool,
+    overlay_requests: Vec<OverlayRequest>,
 }
 
 impl FrameworkState {
@@ -180,6 +230,27 @@ impl FrameworkState {
         })
     }
 
+    /// Opens `content` in a floating layer above the widget tree, placed
+    /// against `anchor`. The overlay stays open until closed through the
+    /// returned handle, or dismissed as set in `options`.
+    pub fn open_overlay<T: Widget + 'static>(
+        content: Wrap<T>,
+        anchor: Anchor,
+        options: OverlayOptions,
+    ) -> OverlayHandle {
+        let (request, handle) =
+            OverlayRequest::new(content.into(), anchor, options);
+        Self::with_mut(|x| {
+            x.overlay_requests.push(request);
+            x.load_requested = true;
+        });
+        handle
+    }
+
+    fn take_overlay_requests() -> Vec<OverlayRequest> {
+        Self::with_mut(|x| std::mem::take(&mut x.overlay_requests))
+    }
+
     pub fn current_focus() -> Option<ID> {
         Self::with(|x| x.current_focused_id)
     }
@@ -202,6 +273,17 @@ impl FrameworkState {
         });
     }
 
+    /// Releases focus if it's held by `node` or anything inside it, for when
+    /// it's hidden or leaves the tree. Otherwise, all input would be sent on
+    /// to a focused widget that nothing passes it to.
+    pub fn release_focus_within(node: &dyn WidgetNode) {
+        if let Some(focus) = Self::current_focus() {
+            if TreeNode::find_info(node, focus).is_some() {
+                Self::release_focus(focus);
+            }
+        }
+    }
+
     pub fn force_release_focus() {
         Self::with_mut(|x| {
             x.current_focused_id = None;
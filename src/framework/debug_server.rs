@@ -0,0 +1,439 @@
+//! A small debugging server, letting external tools inspect a running app.
+//!
+//! The server listens on a local TCP socket and speaks a line-based protocol.
+//! Each request is one line of whitespace-separated words. Each response
+//! starts with a status line, either `ok` or `error <message>`, followed by
+//! zero or more payload lines, and is terminated by a line containing a single
+//! `.`.
+//!
+//! Supported requests:
+//! - `tree`: the widget tree as indented text.
+//! - `tree json`: the widget tree as JSON.
+//! - `props <id>`: one widget's state and properties as JSON.
+//! - `highlight <id>` and `highlight none`: outline a widget on screen.
+//! - `input <event>`: inject an input event, see [parse_event].
+//! - `timings`: the last update and draw times and frame time statistics,
+//!   as JSON.
+//! - `profile on` and `profile off`: start and stop the [Profiler].
+//! - `profile trace`: the recorded frames in the Chrome trace event format.
+//!
+//! Injected events go through `Framework` just like real ones, but are not
+//! reflected in `State`, so for example `State::is_key_down` won't see keys
+//! pressed by `input keydown`.
+
+use std::env;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::profiler::Profiler;
+use super::widgets::{TreeNode, WidgetInfo};
+use crate::game::FrameStats;
+use crate::prelude::*;
+use skia::Point;
+
+/// The address the server listens on, unless overridden by the environment
+/// variable named by [ADDRESS_VARIABLE].
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// The environment variable that overrides [DEFAULT_ADDRESS].
+pub const ADDRESS_VARIABLE: &str = "STACKS_DEBUG_ADDRESS";
+
+pub struct DebugServer {
+    listener: TcpListener,
+    clients: Vec<Client>,
+    highlighted: Option<u64>,
+    highlight_paint: Paint,
+}
+
+impl DebugServer {
+    /// Starts listening on the given address. Only bind to loopback
+    /// addresses, the protocol has no authentication whatsoever.
+    pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: vec![],
+            highlighted: None,
+            highlight_paint: Paint::new_color4f(1.0, 0.0, 1.0, 1.0)
+                .stroke_style()
+                .with_stroke_width(2.0),
+        })
+    }
+
+    /// Starts listening on the address given by the environment, or the
+    /// default one. Failing to do so is reported to stderr, but isn't fatal.
+    ///
+    /// Addresses that aren't loopback addresses are refused, so that setting
+    /// the environment variable can't expose the app to the network.
+    pub fn from_env() -> Option<Self> {
+        let address = env::var(ADDRESS_VARIABLE)
+            .unwrap_or_else(|_| DEFAULT_ADDRESS.to_owned());
+        let loopback = address
+            .to_socket_addrs()
+            .map(|mut addrs| addrs.all(|a| a.ip().is_loopback()));
+        if let Ok(false) = loopback {
+            eprintln!(
+                "Refusing to start debug server on {}: not a loopback address",
+                address
+            );
+            return None;
+        }
+        match Self::bind(&address) {
+            Ok(server) => {
+                eprintln!("Stacks debug server listening on {}", address);
+                Some(server)
+            }
+            Err(e) => {
+                eprintln!("Failed to start debug server on {}: {}", address, e);
+                None
+            }
+        }
+    }
+
+    /// Accepts new clients and answers pending requests. Input events
+    /// requested by clients are pushed into `events`, to be handled by the
+    /// caller.
+    pub fn poll(
+        &mut self,
+        root: &dyn WidgetNode,
+        events: &mut Vec<InputEvent>,
+    ) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() {
+                self.clients.push(Client::new(stream));
+            }
+        }
+
+        let mut clients = std::mem::take(&mut self.clients);
+        for client in &mut clients {
+            for line in client.read_lines() {
+                let response = self.handle(&line, root, events);
+                client.queue(&response);
+            }
+            client.flush();
+        }
+        clients.retain(|c| !c.closed);
+        self.clients = clients;
+    }
+
+    /// Outlines the highlighted widget, if there is one. `canvas` is expected
+    /// to be in window coordinates.
+    pub fn draw(&self, root: &dyn WidgetNode, canvas: &mut Canvas) {
+        if let Some(info) = self.highlighted.and_then(|id| find(root, id)) {
+            canvas.draw_rect(info.bounds, &self.highlight_paint);
+        }
+    }
+
+    fn handle(
+        &mut self,
+        line: &str,
+        root: &dyn WidgetNode,
+        events: &mut Vec<InputEvent>,
+    ) -> String {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.as_slice() {
+            ["tree"] => Ok(TreeNode::capture(root).to_text()),
+            ["tree", "json"] => Ok(TreeNode::capture(root).to_json()),
+            ["props", id] => parse_id(id)
+                .and_then(|id| find(root, id).ok_or_else(no_such_widget))
+                .map(|info| {
+                    TreeNode {
+                        info,
+                        children: vec![],
+                    }
+                    .to_json()
+                }),
+            ["highlight", "none"] => {
+                self.highlighted = None;
+                Ok(String::new())
+            }
+            ["highlight", id] => parse_id(id).map(|id| {
+                self.highlighted = Some(id);
+                String::new()
+            }),
+            ["input", event @ ..] => parse_event(event).map(|e| {
+                events.push(e);
+                String::new()
+            }),
+            ["timings"] => Ok(format!(
+                "{{\"update_ms\":{},\"draw_ms\":{},\"elapsed_s\":{},\
+                 \"update_stats\":{},\"draw_stats\":{}}}",
+                State::last_update_time().as_secs_f64() * 1000.0,
+                State::last_update_time_draw().as_secs_f64() * 1000.0,
+                State::elapsed().as_secs_f64(),
+                stats_json(&State::update_stats()),
+                stats_json(&State::draw_stats()),
+            )),
+            ["profile", "on"] => {
+                Profiler::set_enabled(true);
+                Ok(String::new())
+            }
+            ["profile", "off"] => {
+                Profiler::set_enabled(false);
+                Ok(String::new())
+            }
+            ["profile", "trace"] => Ok(Profiler::chrome_trace()),
+            [] => Err("empty request".to_owned()),
+            _ => Err(format!("unknown request: {}", line.trim())),
+        };
+        match result {
+            Ok(payload) if payload.is_empty() => "ok\n.\n".to_owned(),
+            Ok(payload) => format!("ok\n{}\n.\n", payload.trim_end()),
+            Err(e) => format!("error {}\n.\n", e),
+        }
+    }
+}
+
+struct Client {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    closed: bool,
+}
+
+impl Client {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            incoming: vec![],
+            outgoing: vec![],
+            closed: false,
+        }
+    }
+
+    fn read_lines(&mut self) -> Vec<String> {
+        let mut buf = [0; 1024];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => self.incoming.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.closed = e.kind() != ErrorKind::WouldBlock;
+                    break;
+                }
+            }
+        }
+
+        let mut lines = vec![];
+        while let Some(i) = self.incoming.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.incoming.drain(..=i).collect();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        lines
+    }
+
+    fn queue(&mut self, response: &str) {
+        self.outgoing.extend_from_slice(response.as_bytes());
+    }
+
+    fn flush(&mut self) {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => {
+                    self.closed = true;
+                    return;
+                }
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.closed = e.kind() != ErrorKind::WouldBlock;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn find(root: &dyn WidgetNode, id: u64) -> Option<WidgetInfo> {
+    TreeNode::find_info_by(root, &mut |info: &WidgetInfo| {
+        info.id.as_u64() == id
+    })
+}
+
+fn stats_json(stats: &FrameStats) -> String {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    format!(
+        "{{\"average_ms\":{},\"p50_ms\":{},\"p99_ms\":{},\"min_ms\":{},\
+         \"max_ms\":{},\"target_ms\":{},\"over_budget\":{},\"frames\":{}}}",
+        ms(stats.average()),
+        ms(stats.percentile(0.5)),
+        ms(stats.percentile(0.99)),
+        ms(stats.min()),
+        ms(stats.max()),
+        ms(stats.target()),
+        stats.over_budget(),
+        stats.len(),
+    )
+}
+
+fn no_such_widget() -> String {
+    "no such widget".to_owned()
+}
+
+fn parse_id(s: &str) -> Result<u64, String> {
+    s.trim_start_matches('#')
+        .parse()
+        .map_err(|_| format!("invalid widget ID: {}", s))
+}
+
+fn parse_scalar(s: &str) -> Result<scalar, String> {
+    s.parse().map_err(|_| format!("invalid number: {}", s))
+}
+
+fn parse_point(x: &str, y: &str) -> Result<Point, String> {
+    Ok(Point::new(parse_scalar(x)?, parse_scalar(y)?))
+}
+
+fn parse_button(s: &str) -> Result<MouseButton, String> {
+    Ok(match s {
+        "left" => MouseButton::Left,
+        "right" => MouseButton::Right,
+        "middle" => MouseButton::Middle,
+        s => MouseButton::Other(
+            s.parse()
+                .map_err(|_| format!("invalid mouse button: {}", s))?,
+        ),
+    })
+}
+
+/// Parses an input event, given as words. The accepted forms are:
+/// - `mousemove <x> <y>`
+/// - `mousedown <button> <x> <y>` and `mouseup <button> <x> <y>`, where
+///   `button` is `left`, `right`, `middle` or a number
+/// - `scroll <lines|pixels> <dx> <dy> <x> <y>`
+/// - `keydown <key>` and `keyup <key>`, where `key` is a key name such as
+///   `A`, `Key1`, `Return` or `Escape`
+/// - `char <character>`
+pub fn parse_event(words: &[&str]) -> Result<InputEvent, String> {
+    Ok(match words {
+        ["mousemove", x, y] => InputEvent::MouseMove(parse_point(x, y)?),
+        ["mousedown", b, x, y] => {
+            InputEvent::MouseDown(parse_button(b)?, parse_point(x, y)?)
+        }
+        ["mouseup", b, x, y] => {
+            InputEvent::MouseUp(parse_button(b)?, parse_point(x, y)?)
+        }
+        ["scroll", kind, dx, dy, x, y] => {
+            let delta = parse_point(dx, dy)?;
+            let amount = match *kind {
+                "lines" => ScrollAmount::Lines(delta),
+                "pixels" => ScrollAmount::Pixels(delta),
+                k => return Err(format!("invalid scroll kind: {}", k)),
+            };
+            InputEvent::MouseScroll(amount, parse_point(x, y)?)
+        }
+        ["keydown", k] => InputEvent::KeyDown(parse_keycode(k)?),
+        ["keyup", k] => InputEvent::KeyUp(parse_keycode(k)?),
+        ["char", c] => {
+            let mut chars = c.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => InputEvent::CharReceived(c),
+                _ => return Err(format!("invalid character: {}", c)),
+            }
+        }
+        _ => return Err(format!("invalid event: {}", words.join(" "))),
+    })
+}
+
+macro_rules! keycodes {
+    ($s:expr, $($k:ident),* $(,)?) => {
+        match $s {
+            $(stringify!($k) => Ok(Keycode::$k),)*
+            s => Err(format!("unknown key: {}", s)),
+        }
+    };
+}
+
+fn parse_keycode(s: &str) -> Result<Keycode, String> {
+    keycodes!(
+        s,
+        Key1,
+        Key2,
+        Key3,
+        Key4,
+        Key5,
+        Key6,
+        Key7,
+        Key8,
+        Key9,
+        Key0,
+        A,
+        B,
+        C,
+        D,
+        E,
+        F,
+        G,
+        H,
+        I,
+        J,
+        K,
+        L,
+        M,
+        N,
+        O,
+        P,
+        Q,
+        R,
+        S,
+        T,
+        U,
+        V,
+        W,
+        X,
+        Y,
+        Z,
+        Escape,
+        F1,
+        F2,
+        F3,
+        F4,
+        F5,
+        F6,
+        F7,
+        F8,
+        F9,
+        F10,
+        F11,
+        F12,
+        Insert,
+        Home,
+        Delete,
+        End,
+        PageDown,
+        PageUp,
+        Left,
+        Up,
+        Right,
+        Down,
+        Back,
+        Return,
+        Space,
+        Tab,
+        LAlt,
+        LControl,
+        LShift,
+        RAlt,
+        RControl,
+        RShift,
+        Numpad0,
+        Numpad1,
+        Numpad2,
+        Numpad3,
+        Numpad4,
+        Numpad5,
+        Numpad6,
+        Numpad7,
+        Numpad8,
+        Numpad9,
+        NumpadEnter,
+    )
+}
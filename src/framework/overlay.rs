@@ -0,0 +1,411 @@
+//! Floating content drawn above the widget tree, such as popups, tooltips,
+//! menus and dialogs.
+//!
+//! Overlays are owned by the `Framework`, so they escape the clipping and
+//! transformations of whatever widget opened them. They are opened with
+//! [FrameworkState::open_overlay](super::FrameworkState::open_overlay),
+//! positioned in window coordinates, and get input before the main tree,
+//! topmost first.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::game::ID;
+use crate::prelude::*;
+use skia::Point;
+
+/// What an overlay is positioned against, in window coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Anchor {
+    /// Next to a rectangle, usually a widget's `WidgetState::screen_rect`.
+    Rect(Rect),
+    /// Next to a point, such as where the pointer was clicked.
+    Point(Point),
+    /// In the middle of the window.
+    Centered,
+}
+
+/// Which side of its anchor an overlay goes on.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Placement {
+    Below,
+    Above,
+    Right,
+    Left,
+}
+
+impl Placement {
+    pub fn flipped(self) -> Self {
+        match self {
+            Placement::Below => Placement::Above,
+            Placement::Above => Placement::Below,
+            Placement::Right => Placement::Left,
+            Placement::Left => Placement::Right,
+        }
+    }
+}
+
+/// How an overlay lines up with its anchor, along the side it's placed on.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum OverlayAlign {
+    Start,
+    Center,
+    End,
+}
+
+impl OverlayAlign {
+    /// Where something of the given length starts when lined up with the
+    /// span from `start` to `end`.
+    fn apply(self, start: scalar, end: scalar, length: scalar) -> scalar {
+        match self {
+            OverlayAlign::Start => start,
+            OverlayAlign::Center => (start + end - length) * 0.5,
+            OverlayAlign::End => end - length,
+        }
+    }
+}
+
+pub struct OverlayOptions {
+    pub placement: Placement,
+    pub align: OverlayAlign,
+    /// Space between the anchor and the overlay.
+    pub gap: scalar,
+    /// Whether to move to the other side of the anchor if there isn't
+    /// enough room in the window.
+    pub flip: bool,
+    /// Whether clicking outside the overlay closes it.
+    pub dismiss_on_outside_click: bool,
+    /// Whether the overlay blocks all input to what's below it.
+    pub modal: bool,
+    /// Drawn over the whole window below the overlay.
+    pub backdrop: Option<Paint>,
+    /// Resources to load the content with, usually a
+    /// [ResourceStack::snapshot] taken by the opener when it was loaded.
+    ///
+    /// Overlays aren't in the widget tree, so they don't see resources
+    /// provided by widgets above the opener. Without this, the content is
+    /// loaded with an empty stack, and anything needing `Fonts` or other
+    /// resources won't find them.
+    pub resources: Option<ResourceStack>,
+}
+
+impl OverlayOptions {
+    /// Options for a popup below its anchor, closed by clicking elsewhere.
+    pub fn popup() -> Self {
+        Self {
+            placement: Placement::Below,
+            align: OverlayAlign::Start,
+            gap: 0.0,
+            flip: true,
+            dismiss_on_outside_click: true,
+            modal: false,
+            backdrop: None,
+            resources: None,
+        }
+    }
+
+    /// Options for a modal overlay, dimming and blocking everything below.
+    pub fn modal() -> Self {
+        Self {
+            dismiss_on_outside_click: false,
+            modal: true,
+            backdrop: Some(Paint::new_color4f(0.0, 0.0, 0.0, 0.5)),
+            ..Self::popup()
+        }
+    }
+
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn align(mut self, align: OverlayAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn gap(mut self, gap: scalar) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn resources(mut self, resources: ResourceStack) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+}
+
+impl Default for OverlayOptions {
+    fn default() -> Self {
+        Self::popup()
+    }
+}
+
+/// A handle to an open overlay.
+#[derive(Clone)]
+pub struct OverlayHandle {
+    open: Rc<Cell<bool>>,
+}
+
+impl OverlayHandle {
+    /// Whether the overlay is still open. Overlays can be closed by clicking
+    /// outside them, so this can change on its own.
+    pub fn is_open(&self) -> bool {
+        self.open.get()
+    }
+
+    /// Closes the overlay. It's removed before the next draw.
+    pub fn close(&self) {
+        self.open.set(false);
+    }
+}
+
+/// An overlay waiting to be picked up by the [OverlayLayer].
+pub(crate) struct OverlayRequest {
+    content: Wrap<dyn Widget>,
+    anchor: Anchor,
+    options: OverlayOptions,
+    open: Rc<Cell<bool>>,
+}
+
+impl OverlayRequest {
+    pub(crate) fn new(
+        content: Wrap<dyn Widget>,
+        anchor: Anchor,
+        options: OverlayOptions,
+    ) -> (Self, OverlayHandle) {
+        let open = Rc::new(Cell::new(true));
+        let handle = OverlayHandle { open: open.clone() };
+        let request = Self {
+            content,
+            anchor,
+            options,
+            open,
+        };
+        (request, handle)
+    }
+}
+
+struct Overlay {
+    content: Wrap<dyn Widget>,
+    anchor: Anchor,
+    options: OverlayOptions,
+    resources: ResourceStack,
+    open: Rc<Cell<bool>>,
+    layout_size: LayoutSize,
+    rect: Rect,
+    placed: bool,
+}
+
+impl Overlay {
+    fn matrix(&self) -> Matrix {
+        Matrix::translate((self.rect.left, self.rect.top))
+    }
+
+    /// Works out where the overlay goes in a window of the given size.
+    fn place(&mut self, window: Size) {
+        let layout_size = self.layout_size;
+        let size = match self.anchor {
+            Anchor::Centered => layout_size.layout_one(window),
+            _ => layout_size.get_min(),
+        };
+        let size = Size::new(
+            size.width.min(window.width),
+            size.height.min(window.height),
+        );
+        let anchor = match self.anchor {
+            Anchor::Rect(rect) => rect,
+            Anchor::Point(p) => Rect::from_point_and_size(p, Size::default()),
+            Anchor::Centered => {
+                let origin = Point::new(
+                    (window.width - size.width) * 0.5,
+                    (window.height - size.height) * 0.5,
+                );
+                self.rect = Rect::from_point_and_size(origin, size);
+                return;
+            }
+        };
+
+        let options = &self.options;
+        let mut placement = options.placement;
+        if options.flip {
+            let room = |p| Self::room(p, anchor, window, options.gap);
+            let (needed, flipped) = match placement {
+                Placement::Below | Placement::Above => {
+                    (size.height, placement.flipped())
+                }
+                Placement::Right | Placement::Left => {
+                    (size.width, placement.flipped())
+                }
+            };
+            if room(placement) < needed && room(flipped) > room(placement) {
+                placement = flipped;
+            }
+        }
+
+        let (gap, align) = (options.gap, options.align);
+        let align = |start, end, length| align.apply(start, end, length);
+        let (x, y) = match placement {
+            Placement::Below => (
+                align(anchor.left, anchor.right, size.width),
+                anchor.bottom + gap,
+            ),
+            Placement::Above => (
+                align(anchor.left, anchor.right, size.width),
+                anchor.top - gap - size.height,
+            ),
+            Placement::Right => (
+                anchor.right + gap,
+                align(anchor.top, anchor.bottom, size.height),
+            ),
+            Placement::Left => (
+                anchor.left - gap - size.width,
+                align(anchor.top, anchor.bottom, size.height),
+            ),
+        };
+
+        // Whatever doesn't fit is pushed back inside the window.
+        let x = x.min(window.width - size.width).max(0.0);
+        let y = y.min(window.height - size.height).max(0.0);
+        self.rect = Rect::from_xywh(x, y, size.width, size.height);
+    }
+
+    /// Room left in the window on the given side of `anchor`.
+    fn room(
+        placement: Placement,
+        anchor: Rect,
+        window: Size,
+        gap: scalar,
+    ) -> scalar {
+        let room = match placement {
+            Placement::Below => window.height - anchor.bottom,
+            Placement::Above => anchor.top,
+            Placement::Right => window.width - anchor.right,
+            Placement::Left => anchor.left,
+        };
+        room - gap
+    }
+}
+
+/// The overlays open in a `Framework`, drawn above its root widget.
+pub(crate) struct OverlayLayer {
+    overlays: Vec<Overlay>,
+    window: Size,
+    /// Not the ID of any widget, so hover can be taken away from everything
+    /// below the overlays.
+    id: ID,
+}
+
+impl OverlayLayer {
+    pub(crate) fn new() -> Self {
+        Self {
+            overlays: vec![],
+            window: Size::new_empty(),
+            id: ID::next(),
+        }
+    }
+
+    /// Tells everything in `root` that the pointer isn't over it, for when
+    /// it's over an overlay instead.
+    pub(crate) fn remove_hover<T: Widget + ?Sized>(&self, root: &mut Wrap<T>) {
+        root.input(&InputEvent::RemoveHoverExcept(self.id));
+    }
+
+    pub(crate) fn set_window_size(&mut self, window: Size) {
+        if self.window != window {
+            self.window = window;
+            for overlay in &mut self.overlays {
+                overlay.placed = false;
+            }
+        }
+    }
+
+    /// Takes in newly opened overlays and forgets closed ones.
+    pub(crate) fn adopt(&mut self, requests: Vec<OverlayRequest>) {
+        for mut request in requests {
+            let resources = request
+                .options
+                .resources
+                .take()
+                .unwrap_or_else(ResourceStack::new);
+            self.overlays.push(Overlay {
+                content: request.content,
+                anchor: request.anchor,
+                options: request.options,
+                resources,
+                open: request.open,
+                layout_size: LayoutSize::ZERO,
+                rect: Rect::default(),
+                placed: false,
+            });
+        }
+        self.overlays.retain(|o| o.open.get());
+    }
+
+    pub(crate) fn load(&mut self) {
+        for overlay in &mut self.overlays {
+            overlay.content.load(&mut overlay.resources);
+        }
+    }
+
+    pub(crate) fn update(&mut self) {
+        for overlay in &mut self.overlays {
+            overlay.content.update();
+        }
+    }
+
+    /// Passes an event to the overlays, topmost first. Returns whether it
+    /// was taken, or blocked by an overlay under the pointer or a modal one.
+    ///
+    /// Events sent to the widget holding focus still get through, so that
+    /// for example a drag below can go on over an overlay.
+    pub(crate) fn input(&mut self, event: &InputEvent) -> bool {
+        let focused = matches!(event, InputEvent::Focused(..));
+        for overlay in self.overlays.iter_mut().rev() {
+            if !overlay.open.get() {
+                continue;
+            }
+            let inside = event.position().map(|p| overlay.rect.contains(p));
+            let taken = event
+                .reverse_map_position(overlay.matrix())
+                .map(|e| overlay.content.input(&e))
+                .unwrap_or(false);
+            if taken || (inside == Some(true) && !focused) {
+                return true;
+            }
+            if let (InputEvent::MouseDown(..), Some(false)) = (event, inside) {
+                if overlay.options.dismiss_on_outside_click {
+                    overlay.open.set(false);
+                }
+            }
+            if overlay.options.modal {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub(crate) fn draw(&mut self, canvas: &mut Canvas) {
+        let window = self.window;
+        for overlay in &mut self.overlays {
+            if !overlay.open.get() {
+                continue;
+            }
+            let (layout_size, changed) = overlay.content.size();
+            if changed || !overlay.placed || layout_size != overlay.layout_size
+            {
+                overlay.layout_size = layout_size;
+                overlay.place(window);
+                overlay.placed = true;
+                overlay.content.set_size(overlay.rect.size());
+            }
+            if let Some(backdrop) = &overlay.options.backdrop {
+                canvas.draw_rect(Rect::from_size(window), backdrop);
+            }
+            canvas.save();
+            canvas.concat(&overlay.matrix());
+            overlay.content.draw(canvas);
+            canvas.restore();
+        }
+    }
+}
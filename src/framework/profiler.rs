@@ -0,0 +1,329 @@
+//! Per-widget profiling of the update, input, layout and draw passes.
+//!
+//! Profiling is off by default. Once enabled with [Profiler::set_enabled],
+//! every call to `WidgetState::update`, `input`, `size`, `set_size` and `draw`
+//! is timed and recorded as a [Span], grouped into frames ending on each draw.
+//! The last few frames are kept around, to be inspected with
+//! [Profiler::frames], shown with `widgets::debug::ProfilerOverlay`, or
+//! exported with [Profiler::write_chrome_trace] and opened in
+//! `chrome://tracing` or Perfetto.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::widgets::{short_type_name, write_json_string};
+use crate::game::ID;
+
+/// The pass a [Span] was recorded in.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Pass {
+    Update,
+    Input,
+    /// Layout size queries, that is `Widget::size`.
+    Size,
+    /// Layout itself, that is `Widget::set_size`.
+    SetSize,
+    Draw,
+}
+
+impl Pass {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Update => "update",
+            Self::Input => "input",
+            Self::Size => "size",
+            Self::SetSize => "set_size",
+            Self::Draw => "draw",
+        }
+    }
+}
+
+impl Display for Pass {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.name())
+    }
+}
+
+/// One call into a widget.
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub id: ID,
+    pub type_name: &'static str,
+    pub pass: Pass,
+    pub start: Instant,
+    /// Time spent in this call, children included.
+    pub duration: Duration,
+    /// Time spent in this call, children excluded.
+    pub self_duration: Duration,
+    /// How many profiled calls this one is nested in.
+    pub depth: usize,
+}
+
+/// Everything recorded between two draws.
+#[derive(Clone, Debug)]
+pub struct ProfiledFrame {
+    pub index: u64,
+    pub start: Instant,
+    pub duration: Duration,
+    /// The recorded spans, in the order they finished.
+    pub spans: Vec<Span>,
+}
+
+impl ProfiledFrame {
+    /// Time spent in each widget and pass this frame, children excluded,
+    /// most expensive first.
+    pub fn totals(&self) -> Vec<(ID, &'static str, Pass, Duration)> {
+        let mut totals: HashMap<(ID, Pass), (&'static str, Duration)> =
+            HashMap::new();
+        for span in &self.spans {
+            totals
+                .entry((span.id, span.pass))
+                .or_insert((span.type_name, Duration::default()))
+                .1 += span.self_duration;
+        }
+        let mut totals: Vec<_> = totals
+            .into_iter()
+            .map(|((id, pass), (type_name, d))| (id, type_name, pass, d))
+            .collect();
+        totals.sort_by(|a, b| b.3.cmp(&a.3));
+        totals
+    }
+
+    /// Time spent in the given pass this frame.
+    pub fn pass_total(&self, pass: Pass) -> Duration {
+        self.spans
+            .iter()
+            .filter(|s| s.pass == pass)
+            .map(|s| s.self_duration)
+            .sum()
+    }
+}
+
+pub struct Profiler {
+    history: usize,
+    epoch: Instant,
+    frame_index: u64,
+    frame_start: Instant,
+    open: Vec<OpenSpan>,
+    spans: Vec<Span>,
+    frames: VecDeque<ProfiledFrame>,
+}
+
+struct OpenSpan {
+    id: ID,
+    type_name: &'static str,
+    pass: Pass,
+    start: Instant,
+    children: Duration,
+}
+
+impl Profiler {
+    /// How many frames are kept by default.
+    pub const DEFAULT_HISTORY: usize = 120;
+
+    thread_local!(
+        static ENABLED: Cell<bool> = Cell::new(false);
+        static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::new());
+    );
+
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            history: Self::DEFAULT_HISTORY,
+            epoch: now,
+            frame_index: 0,
+            frame_start: now,
+            open: vec![],
+            spans: vec![],
+            frames: VecDeque::new(),
+        }
+    }
+
+    pub fn is_enabled() -> bool {
+        Self::ENABLED.with(|x| x.get())
+    }
+
+    /// Starts or stops recording. Recorded frames are kept either way.
+    pub fn set_enabled(enabled: bool) {
+        if enabled != Self::is_enabled() {
+            Self::with_mut(|x| {
+                x.open.clear();
+                x.spans.clear();
+                x.frame_start = Instant::now();
+            });
+            Self::ENABLED.with(|x| x.set(enabled));
+        }
+    }
+
+    pub fn toggle() {
+        Self::set_enabled(!Self::is_enabled());
+    }
+
+    /// Sets how many frames are kept.
+    pub fn set_history(frames: usize) {
+        Self::with_mut(|x| {
+            x.history = frames.max(1);
+            while x.frames.len() > x.history {
+                x.frames.pop_front();
+            }
+        });
+    }
+
+    /// Forgets all recorded frames.
+    pub fn clear() {
+        Self::with_mut(|x| x.frames.clear());
+    }
+
+    /// The recorded frames, oldest first.
+    pub fn frames() -> Vec<ProfiledFrame> {
+        Self::with_mut(|x| x.frames.iter().cloned().collect())
+    }
+
+    /// The most recently completed frame.
+    pub fn last_frame() -> Option<ProfiledFrame> {
+        Self::with_mut(|x| x.frames.back().cloned())
+    }
+
+    /// Serializes the recorded frames to the Chrome trace event format.
+    pub fn chrome_trace() -> String {
+        Self::with_mut(|x| x.to_chrome_trace())
+    }
+
+    /// Writes the recorded frames to a file in the Chrome trace event format.
+    pub fn write_chrome_trace(path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, Self::chrome_trace())
+    }
+
+    pub(crate) fn span(
+        id: ID,
+        type_name: &'static str,
+        pass: Pass,
+    ) -> Option<SpanGuard> {
+        if !Self::is_enabled() {
+            return None;
+        }
+        Self::with_mut(|x| {
+            x.open.push(OpenSpan {
+                id,
+                type_name,
+                pass,
+                start: Instant::now(),
+                children: Duration::default(),
+            })
+        });
+        Some(SpanGuard { _private: () })
+    }
+
+    pub(crate) fn end_frame() {
+        if Self::is_enabled() {
+            Self::with_mut(|x| x.finish_frame());
+        }
+    }
+
+    fn end_span(&mut self) {
+        let now = Instant::now();
+        let span = match self.open.pop() {
+            Some(span) => span,
+            None => return,
+        };
+        let duration = now.saturating_duration_since(span.start);
+        if let Some(parent) = self.open.last_mut() {
+            parent.children += duration;
+        }
+        self.spans.push(Span {
+            id: span.id,
+            type_name: span.type_name,
+            pass: span.pass,
+            start: span.start,
+            duration,
+            self_duration: duration
+                .checked_sub(span.children)
+                .unwrap_or_default(),
+            depth: self.open.len(),
+        });
+    }
+
+    fn finish_frame(&mut self) {
+        let now = Instant::now();
+        let frame = ProfiledFrame {
+            index: self.frame_index,
+            start: self.frame_start,
+            duration: now.saturating_duration_since(self.frame_start),
+            spans: std::mem::take(&mut self.spans),
+        };
+        self.frame_index += 1;
+        self.frame_start = now;
+        self.frames.push_back(frame);
+        while self.frames.len() > self.history {
+            self.frames.pop_front();
+        }
+    }
+
+    fn to_chrome_trace(&self) -> String {
+        let mut out = String::from("{\"traceEvents\":[");
+        let mut first = true;
+        for frame in &self.frames {
+            if !first {
+                out.push(',');
+            }
+            first = false;
+            out.push_str("{\"name\":");
+            write_json_string(&mut out, &format!("Frame {}", frame.index));
+            self.write_chrome_times(&mut out, frame.start, frame.duration);
+            out.push_str(",\"cat\":\"frame\",\"args\":{}}");
+            for span in &frame.spans {
+                out.push_str(",{\"name\":");
+                write_json_string(&mut out, &short_type_name(span.type_name));
+                self.write_chrome_times(&mut out, span.start, span.duration);
+                out.push_str(&format!(
+                    ",\"cat\":\"{}\",\"args\":{{\"id\":{},\"type\":",
+                    span.pass,
+                    span.id.as_u64(),
+                ));
+                write_json_string(&mut out, span.type_name);
+                out.push_str(&format!(",\"frame\":{}}}}}", frame.index));
+            }
+        }
+        out.push_str("],\"displayTimeUnit\":\"ms\"}");
+        out
+    }
+
+    fn write_chrome_times(
+        &self,
+        out: &mut String,
+        start: Instant,
+        duration: Duration,
+    ) {
+        // Timestamps are in microseconds.
+        let ts = start.saturating_duration_since(self.epoch);
+        out.push_str(&format!(
+            ",\"ph\":\"X\",\"pid\":1,\"tid\":1,\"ts\":{:.3},\"dur\":{:.3}",
+            ts.as_secs_f64() * 1_000_000.0,
+            duration.as_secs_f64() * 1_000_000.0,
+        ));
+    }
+
+    #[inline]
+    fn with_mut<F, R>(f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        Self::PROFILER.with(|x| f(&mut x.borrow_mut()))
+    }
+}
+
+/// Ends the span started by [Profiler::span] when dropped.
+pub(crate) struct SpanGuard {
+    _private: (),
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        Profiler::with_mut(|x| x.end_span());
+    }
+}
@@ -1,4 +1,5 @@
 use std::any::{Any, TypeId};
+use std::rc::Rc;
 
 use unsafe_any::{UnsafeAny, UnsafeAnyExt};
 
@@ -7,7 +8,12 @@ pub struct ResourceStack<A: ?Sized = dyn UnsafeAny>
 where
     A: UnsafeAnyExt,
 {
-    stack: Vec<(TypeId, Box<A>)>,
+    stack: Vec<Entry<A>>,
+}
+
+struct Entry<A: ?Sized> {
+    id: TypeId,
+    value: Rc<A>,
 }
 
 impl ResourceStack {
@@ -18,7 +24,10 @@ impl ResourceStack {
 
 impl<A: UnsafeAnyExt + ?Sized> ResourceStack<A> {
     pub fn push<T: Any + Implements<A>>(&mut self, val: T) {
-        self.stack.push((TypeId::of::<T>(), val.into_object()));
+        self.stack.push(Entry {
+            id: TypeId::of::<T>(),
+            value: Rc::from(val.into_object()),
+        });
     }
 
     pub fn get<T: Any + Implements<A>>(&self) -> Option<&T> {
@@ -26,14 +35,14 @@ impl<A: UnsafeAnyExt + ?Sized> ResourceStack<A> {
         self.stack
             .iter()
             .rev()
-            .find(|(id, _)| target == *id)
-            .map(|e| unsafe { e.1.downcast_ref_unchecked::<T>() })
+            .find(|e| target == e.id)
+            .map(|e| unsafe { e.value.downcast_ref_unchecked::<T>() })
     }
 
     pub fn pop<T: Any + Implements<A>>(&mut self) {
         let target = TypeId::of::<T>();
-        if let Some((id, _)) = self.stack.iter().rev().next() {
-            assert_eq!(*id, target);
+        if let Some(e) = self.stack.iter().rev().next() {
+            assert_eq!(e.id, target);
             self.stack.pop();
         }
     }
@@ -41,6 +50,22 @@ impl<A: UnsafeAnyExt + ?Sized> ResourceStack<A> {
     pub fn is_empty(&self) -> bool {
         self.stack.is_empty()
     }
+
+    /// A copy of the stack as it is now, for loading widgets later on, away
+    /// from where they are in the tree. The resources themselves are shared
+    /// with this stack rather than cloned.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            stack: self
+                .stack
+                .iter()
+                .map(|e| Entry {
+                    id: e.id,
+                    value: Rc::clone(&e.value),
+                })
+                .collect(),
+        }
+    }
 }
 
 pub unsafe trait Implements<A: ?Sized + UnsafeAnyExt> {
@@ -1,24 +1,42 @@
 pub mod audio;
+pub mod debug;
 pub mod layout;
 pub mod shapes;
 pub mod ui;
 
+mod animation;
 mod backgrounded;
+mod clock;
 mod layout_size;
+mod spring;
 mod text;
+mod timeline;
+mod tooltip;
 mod transform;
+mod tree;
 mod wrap;
 
+pub use animation::{
+    DrawProps, EasingCurve, PropValue, Property, Sequence, Tweened,
+};
 pub use backgrounded::Backgrounded;
+pub use clock::{Clock, Clocked, TimeSource};
 pub use layout_size::{LayoutDimension, LayoutSize};
+pub use spring::{Spring, SpringValue};
 pub use text::{
     FontName, FontResource, FontStyle, Fonts, Text, TextLayoutMode,
 };
+#[cfg(any(feature = "timeline-json", feature = "timeline-ron"))]
+pub use timeline::TimelineError;
+pub use timeline::{Keyframe, Timeline, Track};
+pub use tooltip::Tooltip;
 pub use transform::Transform;
+pub(crate) use tree::{short_type_name, write_json_string};
+pub use tree::{TreeNode, WidgetInfo, WidgetNode};
 pub use wrap::{WidgetBorrow, WidgetBorrowMut, WidgetState, Wrap, Wrappable};
 
 use crate::game::InputEvent;
-use crate::skia::{Canvas, Size};
+use crate::skia::{scalar, Canvas, Size};
 
 use super::resource::ResourceStack;
 
@@ -43,4 +61,27 @@ pub trait Widget {
     fn set_size(&mut self, state: &mut WidgetState, size: Size) {}
 
     fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {}
+
+    /// The distance from the top of this widget to the baseline of its
+    /// first line of text, if it has any. Used to line up text across
+    /// widgets, as with [Align::Baseline](layout::Align::Baseline).
+    fn baseline(&self, state: &WidgetState) -> Option<scalar> {
+        None
+    }
+
+    /// Calls `f` on every direct child of this widget, in drawing order.
+    /// Containers should implement this so the widget tree can be traversed,
+    /// for example by [TreeNode::capture].
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {}
+
+    /// Widget-specific properties, reported alongside the widget when the
+    /// widget tree is inspected.
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// The name of this widget's type.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
@@ -0,0 +1,564 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::prelude::*;
+use skia::Color4f;
+
+/// An easing curve, mapping linear progress from 0 to 1 to eased progress.
+pub type EasingCurve = Rc<dyn Fn(scalar) -> scalar>;
+
+/// Properties applied by `WidgetState` when drawing a widget, on top of
+/// whatever the widget draws itself. These are what tweens animate.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DrawProps {
+    pub alpha: scalar,
+    /// Offset from where the parent put the widget. Doesn't affect layout.
+    pub position: Vector,
+    pub scale: Vector,
+    /// Clockwise rotation, in degrees.
+    pub rotation: scalar,
+    /// Multiplied with everything the widget draws.
+    pub colour: Color4f,
+    /// Overrides the minimum size the widget asks for, if set.
+    pub size: Option<Size>,
+    /// The point scaling and rotation happen around, as a fraction of the
+    /// widget's size.
+    pub origin: Vector,
+    /// An extra transformation, applied in the widget's own coordinates
+    /// before the others.
+    pub transform: Matrix,
+}
+
+impl DrawProps {
+    /// The transformation these properties apply to a widget of the given
+    /// size.
+    pub fn matrix(&self, size: Size) -> Matrix {
+        let origin = Vector::new(
+            self.origin.x * size.width,
+            self.origin.y * size.height,
+        );
+        let mut m = Matrix::translate(self.position + origin);
+        m.pre_rotate(self.rotation, None);
+        m.pre_scale((self.scale.x, self.scale.y), None);
+        m.pre_translate(-origin);
+        m.pre_concat(&self.transform);
+        m
+    }
+
+    /// Whether these properties move, scale or rotate the widget.
+    pub fn is_transformed(&self) -> bool {
+        self.position != Vector::default()
+            || self.scale != Vector::new(1.0, 1.0)
+            || self.rotation != 0.0
+            || !self.transform.is_identity()
+    }
+
+    /// Whether these properties change the colour or opacity of the widget.
+    pub fn is_tinted(&self) -> bool {
+        self.alpha != 1.0 || self.colour != Color4f::new(1.0, 1.0, 1.0, 1.0)
+    }
+
+    /// The current value of the given property. An unset size is reported as
+    /// `fallback_size`.
+    pub fn get(&self, property: Property, fallback_size: Size) -> PropValue {
+        match property {
+            Property::Alpha => PropValue::Alpha(self.alpha),
+            Property::Position => PropValue::Position(self.position),
+            Property::Scale => PropValue::Scale(self.scale),
+            Property::Rotation => PropValue::Rotation(self.rotation),
+            Property::Colour => PropValue::Colour(self.colour),
+            Property::Size => {
+                PropValue::Size(self.size.unwrap_or(fallback_size))
+            }
+            Property::Transform => PropValue::Transform(self.transform),
+        }
+    }
+
+    pub fn set(&mut self, value: PropValue) {
+        match value {
+            PropValue::Alpha(v) => self.alpha = v,
+            PropValue::Position(v) => self.position = v,
+            PropValue::Scale(v) => self.scale = v,
+            PropValue::Rotation(v) => self.rotation = v,
+            PropValue::Colour(v) => self.colour = v,
+            PropValue::Size(v) => self.size = Some(v),
+            PropValue::Transform(v) => self.transform = v,
+        }
+    }
+}
+
+impl Default for DrawProps {
+    fn default() -> Self {
+        Self {
+            alpha: 1.0,
+            position: Vector::default(),
+            scale: Vector::new(1.0, 1.0),
+            rotation: 0.0,
+            colour: Color4f::new(1.0, 1.0, 1.0, 1.0),
+            size: None,
+            origin: Vector::new(0.5, 0.5),
+            transform: Matrix::default(),
+        }
+    }
+}
+
+/// A property of [DrawProps] that can be animated.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Property {
+    Alpha,
+    Position,
+    Scale,
+    Rotation,
+    Colour,
+    Size,
+    Transform,
+}
+
+/// A value for one of the [DrawProps].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PropValue {
+    Alpha(scalar),
+    Position(Vector),
+    Scale(Vector),
+    Rotation(scalar),
+    Colour(Color4f),
+    Size(Size),
+    Transform(Matrix),
+}
+
+impl PropValue {
+    pub fn property(&self) -> Property {
+        match self {
+            PropValue::Alpha(_) => Property::Alpha,
+            PropValue::Position(_) => Property::Position,
+            PropValue::Scale(_) => Property::Scale,
+            PropValue::Rotation(_) => Property::Rotation,
+            PropValue::Colour(_) => Property::Colour,
+            PropValue::Size(_) => Property::Size,
+            PropValue::Transform(_) => Property::Transform,
+        }
+    }
+
+    /// Interpolates towards `to`. Values for different properties don't
+    /// interpolate, `to` is returned as-is.
+    pub fn lerp(self, to: Self, t: scalar) -> Self {
+        match (self, to) {
+            (PropValue::Alpha(a), PropValue::Alpha(b)) => {
+                PropValue::Alpha(a.lerp(b, t))
+            }
+            (PropValue::Position(a), PropValue::Position(b)) => {
+                PropValue::Position(a.lerp(b, t))
+            }
+            (PropValue::Scale(a), PropValue::Scale(b)) => {
+                PropValue::Scale(a.lerp(b, t))
+            }
+            (PropValue::Rotation(a), PropValue::Rotation(b)) => {
+                PropValue::Rotation(a.lerp(b, t))
+            }
+            (PropValue::Colour(a), PropValue::Colour(b)) => {
+                PropValue::Colour(a.lerp(b, t))
+            }
+            (PropValue::Size(a), PropValue::Size(b)) => {
+                PropValue::Size(a.lerp(b, t))
+            }
+            (PropValue::Transform(a), PropValue::Transform(b)) => {
+                PropValue::Transform(a.lerp(b, t))
+            }
+            (_, to) => to,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Tween {
+    start: Duration,
+    duration: Duration,
+    from: Option<PropValue>,
+    to: PropValue,
+    easing: EasingCurve,
+}
+
+impl Tween {
+    fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+
+    fn progress(&self, t: Duration) -> scalar {
+        if t >= self.end() || self.duration == Duration::default() {
+            1.0
+        } else {
+            let t = t.checked_sub(self.start).unwrap_or_default();
+            t.as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+}
+
+/// A set of tweens on a widget's [DrawProps], built up osu!framework style.
+///
+/// Tweens start at the sequence's cursor, which starts at zero and is moved
+/// by [Sequence::delay] and [Sequence::then]. Tweens starting at the same
+/// time run in parallel. Each tween animates from whatever value its property
+/// has when it starts, unless given a starting value.
+///
+/// ```ignore
+/// button.animate(
+///     Sequence::new()
+///         .fade_to(1.0, Duration::from_millis(200), |t| t.ease_out_quad())
+///         .scale_to(Vector::new(1.2, 1.2), Duration::from_millis(200), |t| t)
+///         .then()
+///         .delay(Duration::from_secs(1))
+///         .fade_to(0.0, Duration::from_millis(500), |t| t)
+///         .on_complete(|| println!("Done!")),
+/// );
+/// ```
+pub struct Sequence {
+    tweens: Vec<Tween>,
+    cursor: Duration,
+    repeat: Option<u32>,
+    on_complete: Vec<Box<dyn FnMut()>>,
+}
+
+impl Sequence {
+    pub fn new() -> Self {
+        Self {
+            tweens: vec![],
+            cursor: Duration::default(),
+            repeat: Some(0),
+            on_complete: vec![],
+        }
+    }
+
+    /// Adds a tween of the property `to` belongs to, starting at the cursor.
+    pub fn tween<F>(self, to: PropValue, duration: Duration, easing: F) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.push(None, to, duration, Rc::new(easing))
+    }
+
+    /// Like [Sequence::tween], but starting from `from` instead of the
+    /// property's value at the time.
+    pub fn tween_from<F>(
+        self,
+        from: PropValue,
+        to: PropValue,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.push(Some(from), to, duration, Rc::new(easing))
+    }
+
+    pub fn fade_to<F>(
+        self,
+        alpha: scalar,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Alpha(alpha), duration, easing)
+    }
+
+    pub fn move_to<F>(
+        self,
+        position: Vector,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Position(position), duration, easing)
+    }
+
+    pub fn scale_to<F>(
+        self,
+        scale: Vector,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Scale(scale), duration, easing)
+    }
+
+    pub fn rotate_to<F>(
+        self,
+        degrees: scalar,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Rotation(degrees), duration, easing)
+    }
+
+    pub fn colour_to<F>(
+        self,
+        colour: Color4f,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Colour(colour), duration, easing)
+    }
+
+    pub fn resize_to<F>(self, size: Size, duration: Duration, easing: F) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Size(size), duration, easing)
+    }
+
+    pub fn transform_to<F>(
+        self,
+        transform: Matrix,
+        duration: Duration,
+        easing: F,
+    ) -> Self
+    where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.tween(PropValue::Transform(transform), duration, easing)
+    }
+
+    /// Moves the cursor forward, delaying tweens added afterwards.
+    pub fn delay(mut self, duration: Duration) -> Self {
+        self.cursor += duration;
+        self
+    }
+
+    /// Moves the cursor to the end of the last tween, so tweens added
+    /// afterwards run once every tween so far is done.
+    pub fn then(mut self) -> Self {
+        self.cursor = self.cursor.max(self.duration());
+        self
+    }
+
+    /// Plays the sequence `times` more times after the first. Every property
+    /// animated is reset to its value at the start of the first play before
+    /// each replay.
+    pub fn repeat(mut self, times: u32) -> Self {
+        self.repeat = Some(times);
+        self
+    }
+
+    /// Plays the sequence forever, see [Sequence::repeat].
+    pub fn looping(mut self) -> Self {
+        self.repeat = None;
+        self
+    }
+
+    /// Adds a function called once the sequence is done. Looping sequences
+    /// are never done.
+    ///
+    /// It's called while the animated widget is borrowed, so it must not
+    /// touch that widget or its parents.
+    pub fn on_complete<F: FnMut() + 'static>(mut self, f: F) -> Self {
+        self.on_complete.push(Box::new(f));
+        self
+    }
+
+    /// How long one play of the sequence takes.
+    pub fn duration(&self) -> Duration {
+        self.tweens
+            .iter()
+            .map(Tween::end)
+            .max()
+            .unwrap_or(self.cursor)
+            .max(self.cursor)
+    }
+
+    fn push(
+        mut self,
+        from: Option<PropValue>,
+        to: PropValue,
+        duration: Duration,
+        easing: EasingCurve,
+    ) -> Self {
+        self.tweens.push(Tween {
+            start: self.cursor,
+            duration,
+            from,
+            to,
+            easing,
+        });
+        self
+    }
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A sequence being played on a widget, driven by `WidgetState`.
+pub(crate) struct Playback {
+    sequence: Sequence,
+    start: Option<Duration>,
+    plays_left: Option<u32>,
+    initial: Vec<PropValue>,
+    from: Vec<Option<PropValue>>,
+}
+
+impl Playback {
+    pub(crate) fn new(sequence: Sequence) -> Self {
+        let n = sequence.tweens.len();
+        Self {
+            plays_left: sequence.repeat,
+            sequence,
+            start: None,
+            initial: vec![],
+            from: vec![None; n],
+        }
+    }
+
+    /// Applies the sequence as of `now` to `props`, returning whether it's
+    /// done.
+    pub(crate) fn apply(
+        &mut self,
+        now: Duration,
+        props: &mut DrawProps,
+        size: Size,
+    ) -> bool {
+        let tweens = &self.sequence.tweens;
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                self.start = Some(now);
+                self.initial = tweens
+                    .iter()
+                    .map(|tween| props.get(tween.to.property(), size))
+                    .collect();
+                now
+            }
+        };
+        let length = self.sequence.duration();
+        let mut t = now.checked_sub(start).unwrap_or_default();
+
+        // Replay for as long as needed, finishing every play in between.
+        while t >= length && self.plays_left != Some(0) {
+            if length == Duration::default() {
+                break;
+            }
+            self.apply_at(length, props, size);
+            for value in &self.initial {
+                props.set(*value);
+            }
+            for from in &mut self.from {
+                *from = None;
+            }
+            if let Some(plays_left) = &mut self.plays_left {
+                *plays_left -= 1;
+            }
+            t -= length;
+            self.start = Some(now - t);
+        }
+
+        self.apply_at(t, props, size);
+        let done = t >= length && self.plays_left == Some(0);
+        if done {
+            for f in &mut self.sequence.on_complete {
+                f();
+            }
+        }
+        done
+    }
+
+    fn apply_at(&mut self, t: Duration, props: &mut DrawProps, size: Size) {
+        for (tween, from) in self.sequence.tweens.iter().zip(&mut self.from) {
+            if t < tween.start {
+                continue;
+            }
+            let from = *from.get_or_insert_with(|| {
+                tween
+                    .from
+                    .unwrap_or_else(|| props.get(tween.to.property(), size))
+            });
+            let eased = (tween.easing)(tween.progress(t));
+            props.set(from.lerp(tween.to, eased));
+        }
+    }
+}
+
+/// A single value animated on its own, for widgets animating their own state
+/// rather than their [DrawProps].
+#[derive(Clone)]
+pub struct Tweened<T> {
+    value: T,
+    from: T,
+    to: T,
+    start: Duration,
+    duration: Duration,
+    easing: EasingCurve,
+}
+
+impl<T: Lerp + Copy> Tweened<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            from: value,
+            to: value,
+            start: Duration::default(),
+            duration: Duration::default(),
+            easing: Rc::new(|t| t),
+        }
+    }
+
+    /// The value as of the last call to [Tweened::update].
+    pub fn get(&self) -> T {
+        self.value
+    }
+
+    /// The value being tweened to.
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    /// Jumps to `value`, stopping any tween.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.from = value;
+        self.to = value;
+        self.duration = Duration::default();
+    }
+
+    /// Starts tweening from the current value to `to`, with `now` being the
+    /// current time on whatever clock is passed to [Tweened::update].
+    pub fn tween_to<F>(
+        &mut self,
+        to: T,
+        now: Duration,
+        duration: Duration,
+        easing: F,
+    ) where
+        F: Fn(scalar) -> scalar + 'static,
+    {
+        self.from = self.value;
+        self.to = to;
+        self.start = now;
+        self.duration = duration;
+        self.easing = Rc::new(easing);
+    }
+
+    /// Advances the tween to `now`, returning the new value.
+    pub fn update(&mut self, now: Duration) -> T {
+        let t = now.checked_sub(self.start).unwrap_or_default();
+        self.value = if t >= self.duration {
+            self.to
+        } else {
+            let progress = t.as_secs_f32() / self.duration.as_secs_f32();
+            self.from.lerp(self.to, (self.easing)(progress))
+        };
+        self.value
+    }
+}
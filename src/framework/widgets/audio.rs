@@ -211,4 +211,8 @@ impl<T: Widget + ?Sized> Widget for Audio<T> {
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
         self.child.draw(canvas);
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
 }
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use super::{AudioResource, AudioStream};
 use crate::prelude::*;
 
@@ -12,7 +14,7 @@ pub struct AudioPlayer {
     pub interpolation_factor: f32,
     audio: ResourceUser<AudioResource>,
     path: String,
-    sound: Option<AudioStream>,
+    sound: Option<Rc<AudioStream>>,
     seek_preview_percentage: Option<f32>,
     fft: FftInterpolation,
     size: Size,
@@ -44,6 +46,12 @@ impl AudioPlayer {
         .into()
     }
 
+    /// The stream being played, if it has been loaded. It can be used to
+    /// drive a `Clock`.
+    pub fn stream(&self) -> Option<Rc<AudioStream>> {
+        self.sound.clone()
+    }
+
     pub fn seek_seconds(&mut self, seconds: f64) -> Result<(), ()> {
         if let Some(sound) = &mut self.sound {
             sound.seek(seconds.clamp(0.0, sound.length()?))?;
@@ -88,7 +96,8 @@ impl Widget for AudioPlayer {
                 self.sound = resource
                     .try_access_mut()
                     .unwrap()
-                    .new_audio_stream(&self.path);
+                    .new_audio_stream(&self.path)
+                    .map(Rc::new);
             }
         } else {
             self.audio = ResourceUser::new_none();
@@ -220,4 +229,15 @@ impl Widget for AudioPlayer {
         path.close();
         canvas.draw_path(&path, &self.fft_paint);
     }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        let mut p = vec![("path", self.path.clone())];
+        if let Some(sound) = &self.sound {
+            p.push(("playing", sound.is_playing().to_string()));
+            if let Ok(position) = sound.position() {
+                p.push(("position", position.to_string()));
+            }
+        }
+        p
+    }
 }
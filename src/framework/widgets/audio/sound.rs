@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use super::super::TimeSource;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlayMode {
     Once,
@@ -114,6 +118,25 @@ impl AudioStream {
     }
 }
 
+impl TimeSource for AudioStream {
+    fn position(&self) -> Option<Duration> {
+        let position = AudioStream::position(self).ok()?;
+        Some(Duration::from_secs_f64(position.max(0.0)))
+    }
+
+    fn seek(&self, position: Duration) {
+        let _ = AudioStream::seek(self, position.as_secs_f64());
+    }
+
+    fn set_rate(&self, rate: f64) {
+        let _ = self.set_speed(rate as f32);
+    }
+
+    fn set_running(&self, running: bool) {
+        let _ = self.set_playing(running);
+    }
+}
+
 pub struct Sample {
     pub(super) inner: allegro_audio::Sample,
 }
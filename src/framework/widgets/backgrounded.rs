@@ -64,4 +64,9 @@ impl<B: Widget + ?Sized, F: Widget + ?Sized> Widget for Backgrounded<B, F> {
         self.background.update();
         self.foreground.update();
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.background);
+        f(&self.foreground);
+    }
 }
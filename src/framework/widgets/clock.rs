@@ -0,0 +1,210 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// Something a [Clock] can follow instead of counting time by itself, such
+/// as an audio stream.
+pub trait TimeSource {
+    /// The current position, or `None` if it's unavailable.
+    fn position(&self) -> Option<Duration>;
+
+    fn seek(&self, position: Duration);
+
+    fn set_rate(&self, rate: f64);
+
+    fn set_running(&self, running: bool);
+}
+
+impl<T: TimeSource + ?Sized> TimeSource for Rc<T> {
+    fn position(&self) -> Option<Duration> {
+        (**self).position()
+    }
+
+    fn seek(&self, position: Duration) {
+        (**self).seek(position)
+    }
+
+    fn set_rate(&self, rate: f64) {
+        (**self).set_rate(rate)
+    }
+
+    fn set_running(&self, running: bool) {
+        (**self).set_running(running)
+    }
+}
+
+/// A clock that can be paused, sought and sped up or slowed down.
+///
+/// Clocks are provided to a subtree by [Clocked], and are ticked once per
+/// draw. Widgets read the nearest one through [WidgetState::elapsed] and
+/// [WidgetState::delta].
+pub struct Clock {
+    elapsed: Duration,
+    delta: Duration,
+    rate: f64,
+    running: bool,
+    source: Option<Box<dyn TimeSource>>,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Self {
+            elapsed: Duration::default(),
+            delta: Duration::default(),
+            rate: 1.0,
+            running: true,
+            source: None,
+        }
+    }
+
+    /// Time elapsed on this clock.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Time elapsed on this clock during the last tick.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Sets how fast this clock runs compared to its parent. Negative rates
+    /// are treated as zero.
+    pub fn set_rate(&mut self, rate: f64) {
+        self.rate = rate.max(0.0);
+        if let Some(source) = &self.source {
+            source.set_rate(self.rate);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn set_running(&mut self, running: bool) {
+        self.running = running;
+        if let Some(source) = &self.source {
+            source.set_running(running);
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.set_running(false);
+    }
+
+    pub fn resume(&mut self) {
+        self.set_running(true);
+    }
+
+    /// Pauses the clock if running, resumes it otherwise. Returns whether
+    /// it's running afterwards.
+    pub fn toggle(&mut self) -> bool {
+        self.set_running(!self.running);
+        self.running
+    }
+
+    pub fn seek(&mut self, position: Duration) {
+        self.elapsed = position;
+        if let Some(source) = &self.source {
+            source.seek(position);
+        }
+    }
+
+    /// Makes this clock follow `source`, which is then paused, sought and
+    /// sped up along with the clock.
+    pub fn set_source(&mut self, source: Option<Box<dyn TimeSource>>) {
+        if let Some(source) = &source {
+            source.set_rate(self.rate);
+            source.set_running(self.running);
+        }
+        self.source = source;
+    }
+
+    /// Advances the clock, given how much time passed on the parent clock.
+    pub fn tick(&mut self, parent_delta: Duration) {
+        let position = self.source.as_ref().and_then(|s| s.position());
+        if let Some(position) = position {
+            // Sources going backwards have been sought, not ticked.
+            self.delta = position.checked_sub(self.elapsed).unwrap_or_default();
+            self.elapsed = position;
+        } else if self.running {
+            self.delta = parent_delta.mul_f64(self.rate);
+            self.elapsed += self.delta;
+        } else {
+            self.delta = Duration::default();
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Provides its own [Clock] to its child.
+pub struct Clocked<T: Widget + ?Sized> {
+    child: Wrap<T>,
+    clock: ResourceHoster<Clock>,
+}
+
+impl<T: Widget + ?Sized> Clocked<T> {
+    pub fn new(child: Wrap<T>) -> Wrap<Self> {
+        FrameworkState::request_load();
+        Self {
+            child,
+            clock: ResourceHoster::new(Clock::new()),
+        }
+        .into()
+    }
+
+    pub fn clock(&self) -> ResourceUsageMut<Clock> {
+        self.clock.access_mut()
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for Clocked<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        stack.push(self.clock.new_user());
+        self.child.load(stack);
+        stack.pop::<ResourceUser<Clock>>();
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.child.update();
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        self.child.input(event)
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        self.child.size()
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.child.set_size(size);
+    }
+
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
+        self.clock.access_mut().tick(state.delta());
+        self.child.draw(canvas);
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        let clock = self.clock.access();
+        vec![
+            ("elapsed", format!("{:?}", clock.elapsed())),
+            ("rate", clock.rate().to_string()),
+            ("running", clock.is_running().to_string()),
+        ]
+    }
+}
@@ -0,0 +1,84 @@
+//! Widgets for looking into a running app.
+
+mod frame_graph;
+mod profiler_overlay;
+
+pub use frame_graph::{FrameGraph, FrameGraphSource};
+pub use profiler_overlay::ProfilerOverlay;
+
+use std::time::Duration;
+
+use super::{FontResource, FontStyle};
+use crate::game::ID;
+use crate::prelude::*;
+
+/// Draws single lines of text in the default font, for debug widgets that
+/// need labels without the layout machinery of `Text`.
+struct DebugFont {
+    ft: ResourceUser<FontResource>,
+    face_id: Option<ID>,
+    size: isize,
+}
+
+impl DebugFont {
+    fn new(size: scalar) -> Self {
+        Self {
+            ft: ResourceUser::new_none(),
+            face_id: None,
+            size: size.to_26dot6(),
+        }
+    }
+
+    fn load(&mut self, stack: &mut ResourceStack) {
+        if let Some(f) = stack.get::<ResourceUser<FontResource>>() {
+            if f != &self.ft {
+                self.ft = f.clone();
+                self.face_id = f
+                    .try_access()
+                    .and_then(|ft| ft.get_face(None, FontStyle::Regular));
+            }
+        }
+    }
+
+    /// Draws `text` with its baseline starting at `origin`, cutting it short
+    /// at `max_width`. Returns the width drawn. Draws nothing if no font
+    /// resource is available.
+    fn draw(
+        &self,
+        canvas: &mut Canvas,
+        text: &str,
+        origin: Vector,
+        max_width: scalar,
+        paint: &Paint,
+    ) -> scalar {
+        let face_id = match self.face_id {
+            Some(id) => id,
+            None => return 0.0,
+        };
+        let mut ft = match self.ft.try_access_mut() {
+            Some(ft) => ft,
+            None => return 0.0,
+        };
+        let font = match ft.get_font(face_id, Some(self.size)) {
+            Some(font) => font,
+            None => return 0.0,
+        };
+        let mut x = 0.0;
+        for c in text.chars() {
+            let (path, advance) = font.get_char(c);
+            if x + advance > max_width {
+                break;
+            }
+            canvas.save();
+            canvas.translate(origin + Vector::new(x, 0.0));
+            canvas.draw_path(&path, paint);
+            canvas.restore();
+            x += advance;
+        }
+        x
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
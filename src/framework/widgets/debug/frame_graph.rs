@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use super::{millis, DebugFont};
+use crate::game::FrameStats;
+use crate::prelude::*;
+
+/// Which loop a [FrameGraph] shows.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum FrameGraphSource {
+    Update,
+    Draw,
+}
+
+/// A scrolling graph of recent frame times, newest on the right, with a line
+/// at the target frame time.
+///
+/// A summary line with the average, 99th percentile and maximum frame times
+/// is drawn on top when a `Fonts` resource is available.
+pub struct FrameGraph {
+    pub source: FrameGraphSource,
+    /// The frame time at the top of the graph. Longer frames are clipped.
+    pub scale: Duration,
+    pub show_summary: bool,
+    pub background_paint: Paint,
+    pub bar_paint: Paint,
+    /// Paint for frames over budget, see [FrameStats::is_over_budget].
+    pub over_budget_paint: Paint,
+    pub target_paint: Paint,
+    pub text_paint: Paint,
+    layout_size: LayoutSize,
+    size: Size,
+    font: DebugFont,
+}
+
+impl FrameGraph {
+    pub fn new(
+        size: LayoutSize,
+        source: FrameGraphSource,
+        bar_paint: Paint,
+    ) -> Wrap<Self> {
+        FrameworkState::request_load();
+        Self {
+            source,
+            scale: Duration::from_millis(33),
+            show_summary: true,
+            background_paint: Paint::new_color4f(0.0, 0.0, 0.0, 0.6),
+            bar_paint,
+            over_budget_paint: Paint::new_color4f(1.0, 0.3, 0.2, 1.0),
+            target_paint: Paint::new_color4f(1.0, 1.0, 1.0, 0.5)
+                .stroke_style()
+                .with_stroke_width(1.0),
+            text_paint: Paint::new_color4f(1.0, 1.0, 1.0, 1.0).anti_alias(),
+            layout_size: size,
+            size: Size::new_empty(),
+            font: DebugFont::new(11.0),
+        }
+        .into()
+    }
+
+    fn stats(&self) -> FrameStats {
+        match self.source {
+            FrameGraphSource::Update => State::update_stats(),
+            FrameGraphSource::Draw => State::draw_stats(),
+        }
+    }
+
+    fn y_of(&self, d: Duration) -> scalar {
+        let scale = self.scale.as_secs_f32();
+        let f = if scale > 0.0 {
+            (d.as_secs_f32() / scale).min(1.0)
+        } else {
+            1.0
+        };
+        self.size.height * (1.0 - f)
+    }
+}
+
+impl Widget for FrameGraph {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.font.load(stack);
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {}
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        let stats = self.stats();
+        canvas.draw_rect(Rect::from_size(self.size), &self.background_paint);
+
+        let bar_width = self.size.width / stats.capacity() as scalar;
+        let first = stats.capacity() - stats.len();
+        for (i, d) in stats.iter().enumerate() {
+            let x = (first + i) as scalar * bar_width;
+            let paint = if stats.is_over_budget(d) {
+                &self.over_budget_paint
+            } else {
+                &self.bar_paint
+            };
+            let rect = Rect::new(
+                x,
+                self.y_of(d),
+                x + bar_width.max(1.0),
+                self.size.height,
+            );
+            canvas.draw_rect(rect, paint);
+        }
+
+        let y = self.y_of(stats.target());
+        canvas.draw_line((0.0, y), (self.size.width, y), &self.target_paint);
+
+        if self.show_summary {
+            let summary = format!(
+                "{:.0} fps  avg {:.2} ms  p99 {:.2} ms  max {:.2} ms  \
+                 over budget {}",
+                stats.fps(),
+                millis(stats.average()),
+                millis(stats.percentile(0.99)),
+                millis(stats.max()),
+                stats.over_budget(),
+            );
+            self.font.draw(
+                canvas,
+                &summary,
+                Vector::new(4.0, 14.0),
+                self.size.width - 8.0,
+                &self.text_paint,
+            );
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("source", format!("{:?}", self.source))]
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use super::{millis, DebugFont};
+use crate::framework::profiler::{Pass, ProfiledFrame, Profiler};
+use crate::framework::widgets::short_type_name;
+use crate::prelude::*;
+
+/// Shows the last frame recorded by the [Profiler] as a flame graph over its
+/// child, along with the widgets that took the longest.
+///
+/// Pressing the toggle key, F3 by default, turns both the overlay and the
+/// profiler on and off. Labels are only drawn when a `Fonts` resource is
+/// available.
+pub struct ProfilerOverlay<T: Widget + ?Sized> {
+    pub key: Keycode,
+    /// The frame duration spanning the whole width of the overlay. Frames
+    /// taking longer are squeezed to fit.
+    pub time_window: Duration,
+    /// How many of the most expensive widgets to list.
+    pub top: usize,
+    pub background_paint: Paint,
+    pub text_paint: Paint,
+    child: Wrap<T>,
+    font: DebugFont,
+    visible: bool,
+    lock: bool,
+    size: Size,
+}
+
+impl<T: Widget + ?Sized> ProfilerOverlay<T> {
+    const BAR_HEIGHT: scalar = 16.0;
+    const LINE_HEIGHT: scalar = 14.0;
+    const PADDING: scalar = 4.0;
+
+    pub fn new(child: Wrap<T>) -> Wrap<Self> {
+        FrameworkState::request_load();
+        Self {
+            key: Keycode::F3,
+            time_window: Duration::from_micros(16_667),
+            top: 5,
+            background_paint: Paint::new_color4f(0.0, 0.0, 0.0, 0.75),
+            text_paint: Paint::new_color4f(1.0, 1.0, 1.0, 1.0).anti_alias(),
+            child,
+            font: DebugFont::new(11.0),
+            visible: false,
+            lock: false,
+            size: Size::new_empty(),
+        }
+        .into()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the overlay, starting or stopping the profiler along
+    /// with it.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+        Profiler::set_enabled(visible);
+    }
+
+    fn pass_paint(pass: Pass) -> Paint {
+        let (r, g, b) = match pass {
+            Pass::Update => (0.35, 0.55, 0.95),
+            Pass::Input => (0.7, 0.45, 0.9),
+            Pass::Size => (0.95, 0.7, 0.25),
+            Pass::SetSize => (0.95, 0.45, 0.25),
+            Pass::Draw => (0.35, 0.8, 0.45),
+        };
+        Paint::new_color4f(r, g, b, 0.9)
+    }
+
+    fn draw_frame(&self, canvas: &mut Canvas, frame: &ProfiledFrame) {
+        let depth = frame.spans.iter().map(|s| s.depth + 1).max().unwrap_or(0);
+        let mut totals = frame.totals();
+        totals.truncate(self.top);
+        let text_lines = totals.len() + 1;
+        let height = Self::PADDING * 2.0
+            + text_lines as scalar * Self::LINE_HEIGHT
+            + depth as scalar * Self::BAR_HEIGHT;
+        let width = self.size.width;
+        let top = (self.size.height - height).max(0.0);
+        canvas.draw_rect(
+            Rect::from_xywh(0.0, top, width, height),
+            &self.background_paint,
+        );
+
+        // Summary and most expensive widgets
+        let mut y = top + Self::PADDING + Self::LINE_HEIGHT;
+        let summary = format!(
+            "Frame {}: {:.2} ms (update {:.2}, input {:.2}, size {:.2}, \
+             set_size {:.2}, draw {:.2})",
+            frame.index,
+            millis(frame.duration),
+            millis(frame.pass_total(Pass::Update)),
+            millis(frame.pass_total(Pass::Input)),
+            millis(frame.pass_total(Pass::Size)),
+            millis(frame.pass_total(Pass::SetSize)),
+            millis(frame.pass_total(Pass::Draw)),
+        );
+        let max_width = width - Self::PADDING * 2.0;
+        let origin = Vector::new(Self::PADDING, y);
+        self.font
+            .draw(canvas, &summary, origin, max_width, &self.text_paint);
+        for (id, type_name, pass, duration) in totals {
+            y += Self::LINE_HEIGHT;
+            let line = format!(
+                "{:.3} ms  {} {} {}",
+                millis(duration),
+                pass,
+                short_type_name(type_name),
+                id,
+            );
+            let origin = Vector::new(Self::PADDING, y);
+            self.font
+                .draw(canvas, &line, origin, max_width, &self.text_paint);
+        }
+
+        // Flame graph, outermost calls on top
+        let window = self.time_window.max(frame.duration).as_secs_f32();
+        let scale = width / window;
+        let bars_top = y + Self::PADDING;
+        for span in &frame.spans {
+            let start = span.start.saturating_duration_since(frame.start);
+            let x = start.as_secs_f32() * scale;
+            let w = (span.duration.as_secs_f32() * scale).max(1.0);
+            let y = bars_top + span.depth as scalar * Self::BAR_HEIGHT;
+            let rect = Rect::from_xywh(x, y, w, Self::BAR_HEIGHT - 1.0);
+            canvas.draw_rect(rect, &Self::pass_paint(span.pass));
+            if w > 24.0 {
+                let label = format!(
+                    "{} {:.2}",
+                    short_type_name(span.type_name),
+                    millis(span.duration)
+                );
+                let origin = Vector::new(x + 2.0, y + Self::BAR_HEIGHT - 4.0);
+                self.font.draw(
+                    canvas,
+                    &label,
+                    origin,
+                    w - 4.0,
+                    &self.text_paint,
+                );
+            }
+        }
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for ProfilerOverlay<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.font.load(stack);
+        self.child.load(stack);
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.child.update();
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown(k) if *k == self.key => {
+                if !self.lock {
+                    self.lock = true;
+                    self.set_visible(!self.visible);
+                }
+                true
+            }
+            InputEvent::KeyUp(k) if *k == self.key => {
+                self.lock = false;
+                true
+            }
+            _ => self.child.input(event),
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        self.child.size()
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        self.child.set_size(size);
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        self.child.draw(canvas);
+        if self.visible {
+            if let Some(frame) = Profiler::last_frame() {
+                self.draw_frame(canvas, &frame);
+            }
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("visible", self.visible.to_string())]
+    }
+}
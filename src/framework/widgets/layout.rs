@@ -1,21 +1,40 @@
 mod ab;
+mod align;
 mod center_container;
 mod contained;
 mod container;
+mod flow_container;
 mod fullscreen_container;
+mod grid_container;
 mod h_container;
 mod margin_container;
+mod screen_stack;
 mod scroll_container;
+mod scroll_offset;
 mod size_fill_container;
+mod split_container;
+mod stack_container;
 mod v_container;
+mod virtual_list;
 
 pub use ab::{TimeReport, AB};
+pub use align::{Align, Justify};
 pub use center_container::CenterContainer;
 pub use contained::{ContainMode, Contained};
-pub use container::{ContainerDimension, ContainerSize};
+pub use container::{Axes, ContainerDimension, ContainerSize};
+pub use flow_container::{FlowContainer, FlowContainerDyn};
 pub use fullscreen_container::FullscreenContainer;
+pub use grid_container::{GridCell, GridContainer, GridContainerDyn, Track};
 pub use h_container::{HContainer, HContainerDyn};
 pub use margin_container::{Margin, MarginContainer};
-pub use scroll_container::ScrollContainer;
+pub use screen_stack::{
+    Screen, ScreenNavigator, ScreenStack, Transition, TransitionKind,
+};
+pub use scroll_container::{ScrollContainer, Scrollbars};
 pub use size_fill_container::SizeFillContainer;
+pub use split_container::{HSplit, SplitSide, VSplit};
+pub use stack_container::{
+    AnchorPoint, StackContainer, StackContainerDyn, StackPosition,
+};
 pub use v_container::{VContainer, VContainerDyn};
+pub use virtual_list::VirtualList;
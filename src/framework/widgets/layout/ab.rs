@@ -12,7 +12,8 @@ pub struct AB<A: TimeReport + ?Sized, B: Widget + ?Sized> {
     b: Wrap<B>,
     b_size: LayoutSize,
     fade_time: Duration,
-    running: Option<(Duration, Duration)>,
+    /// The duration of the run and when it started, if it has yet.
+    running: Option<(Duration, Option<Duration>)>,
     just_switched: bool,
     size: Size,
     schedule_set_size: bool,
@@ -42,14 +43,17 @@ impl<A: TimeReport, B: Widget + ?Sized> AB<A, B> {
 
     pub fn run(&mut self, duration: Duration) {
         if self.a.is_some() && self.running.is_none() {
-            self.running = Some((duration, State::elapsed_draw()));
+            // Started on the next draw, where the widget's clock is known.
+            self.running = Some((duration, None));
         }
     }
 
-    fn tick_forward(&mut self) -> bool {
-        if let Some((du, start)) = &self.running {
+    fn tick_forward(&mut self, now: Duration) -> bool {
+        if let Some((du, start)) = &mut self.running {
+            let start = *start.get_or_insert(now);
             let f = self.a.as_mut().unwrap();
-            let delta = (State::elapsed_draw() - *start).as_secs_f32();
+            let delta =
+                now.checked_sub(start).unwrap_or_default().as_secs_f32();
             let du = du.as_secs_f32();
             let fade_time = self.fade_time.as_secs_f32();
             let delta_scaled = delta / du;
@@ -125,8 +129,8 @@ impl<A: TimeReport, B: Widget + ?Sized> Widget for AB<A, B> {
         }
     }
 
-    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
-        let t = self.tick_forward();
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
+        let t = self.tick_forward(state.elapsed());
         self.just_switched |= t;
         if self.schedule_set_size {
             self.schedule_set_size = false;
@@ -149,4 +153,15 @@ impl<A: TimeReport, B: Widget + ?Sized> Widget for AB<A, B> {
             }
         }
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        if let Some((a, _, _)) = &self.a {
+            f(a);
+        }
+        f(&self.b);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("running", self.is_running().to_string())]
+    }
 }
@@ -0,0 +1,92 @@
+use super::super::LayoutDimension;
+use crate::skia::scalar;
+
+/// Where a child goes across a linear container, such as vertically in an
+/// [HContainer](super::HContainer).
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Takes up all the space, as if expanding.
+    Stretch,
+    /// Lines up the baselines of children aligned this way, for rows of
+    /// text. Children without a baseline, and children of containers that
+    /// don't line up baselines, such as a [VContainer](super::VContainer),
+    /// go at the start instead.
+    Baseline,
+}
+
+impl Align {
+    /// The size of a child across the container, given the space there.
+    pub(crate) fn cross_size(
+        self,
+        dim: LayoutDimension,
+        space: scalar,
+    ) -> scalar {
+        if dim.expand.is_some() || self == Align::Stretch {
+            dim.max.map_or(space, |max| space.min(max))
+        } else {
+            dim.preferred(space).min(space)
+        }
+    }
+
+    /// Where a child of the given size starts across the container.
+    pub(crate) fn cross_offset(self, size: scalar, space: scalar) -> scalar {
+        match self {
+            Align::Start | Align::Stretch | Align::Baseline => 0.0,
+            Align::Center => (space - size) * 0.5,
+            Align::End => space - size,
+        }
+    }
+}
+
+impl Default for Align {
+    fn default() -> Self {
+        Self::Start
+    }
+}
+
+/// How a linear container spreads out leftover space along its axis, when
+/// none of its children expand to take it.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Justify {
+    Start,
+    Center,
+    End,
+    /// Between children, with none before the first or after the last.
+    SpaceBetween,
+    /// Around each child, so the space at either end is half of that
+    /// between children.
+    SpaceAround,
+    /// Evenly between children and at either end.
+    SpaceEvenly,
+}
+
+impl Justify {
+    /// Where the first of `count` children starts, and the extra space put
+    /// between each of them, given `space` left over.
+    pub(crate) fn distribute(
+        self,
+        space: scalar,
+        count: usize,
+    ) -> (scalar, scalar) {
+        let n = count as scalar;
+        match self {
+            Justify::Start => (0.0, 0.0),
+            Justify::Center => (space * 0.5, 0.0),
+            Justify::End => (space, 0.0),
+            Justify::SpaceBetween if count > 1 => (0.0, space / (n - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround if count > 0 => (space / n * 0.5, space / n),
+            Justify::SpaceAround => (space * 0.5, 0.0),
+            Justify::SpaceEvenly => (space / (n + 1.0), space / (n + 1.0)),
+        }
+    }
+}
+
+impl Default for Justify {
+    fn default() -> Self {
+        Self::Start
+    }
+}
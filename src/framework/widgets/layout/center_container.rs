@@ -59,4 +59,8 @@ impl<T: Widget + ?Sized> Widget for CenterContainer<T> {
         self.child.draw(canvas);
         canvas.restore();
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
 }
@@ -73,4 +73,8 @@ impl<T: Widget> Widget for Contained<T> {
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
         self.inner.draw(canvas);
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.inner);
+    }
 }
@@ -1,22 +1,32 @@
 use super::super::{LayoutDimension, LayoutSize, Widget, Wrap};
+use super::align::Align;
 use crate::skia::{scalar, Size, Vector};
 
+/// How much space a container asks for, on top of what its children need.
+///
+/// More fields may be added over time, so build these from
+/// [ContainerSize::ZERO] or [ContainerSize::min] and the builder methods
+/// rather than with struct literals.
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct ContainerSize {
     pub width: ContainerDimension,
     pub height: ContainerDimension,
+    pub aspect_ratio: Option<scalar>,
 }
 
 impl ContainerSize {
     pub const ZERO: Self = Self {
         width: ContainerDimension::ZERO,
         height: ContainerDimension::ZERO,
+        aspect_ratio: None,
     };
 
     pub fn min(width: scalar, height: scalar) -> Self {
         Self {
             width: ContainerDimension::min(width),
             height: ContainerDimension::min(height),
+            aspect_ratio: None,
         }
     }
 
@@ -50,10 +60,36 @@ impl ContainerSize {
         self
     }
 
+    pub fn max_width(mut self, max: scalar) -> Self {
+        self.width.max = Some(max);
+        self
+    }
+
+    pub fn max_height(mut self, max: scalar) -> Self {
+        self.height.max = Some(max);
+        self
+    }
+
+    pub fn relative_width(mut self, fraction: scalar) -> Self {
+        self.width.relative = Some(fraction);
+        self
+    }
+
+    pub fn relative_height(mut self, fraction: scalar) -> Self {
+        self.height.relative = Some(fraction);
+        self
+    }
+
+    pub fn with_aspect_ratio(mut self, ratio: Option<scalar>) -> Self {
+        self.aspect_ratio = ratio;
+        self
+    }
+
     pub fn apply(&self, child_size: &LayoutSize) -> LayoutSize {
         LayoutSize {
             width: self.width.apply(child_size.width),
             height: self.height.apply(child_size.height),
+            aspect_ratio: self.aspect_ratio,
         }
     }
 }
@@ -63,26 +99,33 @@ impl From<LayoutSize> for ContainerSize {
         Self {
             width: s.width.into(),
             height: s.height.into(),
+            aspect_ratio: s.aspect_ratio,
         }
     }
 }
 
+/// One axis of a [ContainerSize], built the same way.
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct ContainerDimension {
     pub min: Option<scalar>,
     pub expand: Option<scalar>,
+    pub max: Option<scalar>,
+    pub relative: Option<scalar>,
 }
 
 impl ContainerDimension {
     pub const ZERO: Self = Self {
         min: None,
         expand: None,
+        max: None,
+        relative: None,
     };
 
     pub fn min(min: scalar) -> Self {
         Self {
             min: Some(min),
-            expand: None,
+            ..Self::ZERO
         }
     }
 
@@ -101,12 +144,24 @@ impl ContainerDimension {
         self
     }
 
+    pub fn with_max(mut self, max: Option<scalar>) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_relative(mut self, fraction: Option<scalar>) -> Self {
+        self.relative = fraction;
+        self
+    }
+
     pub fn apply(&self, child_dim: LayoutDimension) -> LayoutDimension {
         let child_min = child_dim.min;
         let min = self.min.map_or(child_min, |c| child_min.max(c));
         LayoutDimension {
             min,
             expand: self.expand,
+            max: self.max,
+            relative: self.relative,
         }
     }
 }
@@ -116,13 +171,84 @@ impl From<LayoutDimension> for ContainerDimension {
         Self {
             min: Some(d.min),
             expand: d.expand,
+            max: d.max,
+            relative: d.relative,
+        }
+    }
+}
+
+/// A set of axes, such as those a [StackContainer](super::StackContainer)
+/// sizes a child relative to it along, or those a
+/// [ScrollContainer](super::ScrollContainer) scrolls.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Axes {
+    None,
+    X,
+    Y,
+    Both,
+}
+
+impl Axes {
+    pub fn has_x(self) -> bool {
+        matches!(self, Axes::X | Axes::Both)
+    }
+
+    pub fn has_y(self) -> bool {
+        matches!(self, Axes::Y | Axes::Both)
+    }
+}
+
+impl Default for Axes {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// Shares out `space` along a container's axis between children with the
+/// given dimensions, returning the size of each.
+///
+/// Every child first gets its [preferred](LayoutDimension::preferred) size.
+/// What's left goes to expanding children by their weights, and whatever a
+/// child can't take because of its maximum goes to the others.
+pub(crate) fn distribute(
+    dims: &[LayoutDimension],
+    space: scalar,
+) -> Vec<scalar> {
+    let mut sizes: Vec<scalar> =
+        dims.iter().map(|d| d.preferred(space)).collect();
+    let mut left = space - sizes.iter().sum::<scalar>();
+    let mut open: Vec<usize> = (0..dims.len())
+        .filter(|&i| dims[i].expand.is_some())
+        .collect();
+    while left > 0.0 && !open.is_empty() {
+        let total: scalar = open.iter().filter_map(|&i| dims[i].expand).sum();
+        if total <= 0.0 {
+            break;
+        }
+        let mut capped = false;
+        let mut given = 0.0;
+        open.retain(|&i| {
+            let share = left * dims[i].expand.unwrap_or(0.0) / total;
+            let size = dims[i].clamp(sizes[i] + share);
+            given += size - sizes[i];
+            sizes[i] = size;
+            let full = dims[i].max.map_or(false, |max| size >= max);
+            capped |= full;
+            !full
+        });
+        left -= given;
+        if !capped {
+            break;
         }
     }
+    sizes
 }
 
 pub struct ChildState {
     pub layout_size: LayoutSize,
     pub position: Vector,
+    /// Overrides the container's alignment for this child.
+    pub align: Option<Align>,
     size: Size,
     changed: bool,
     children_changed: bool,
@@ -133,6 +259,7 @@ impl ChildState {
         Self {
             layout_size: LayoutSize::ZERO,
             position: (0.0, 0.0).into(),
+            align: None,
             size: Size::new_empty(),
             changed: true,
             children_changed: true,
@@ -162,3 +289,175 @@ impl ChildState {
         }
     }
 }
+
+/// The methods for adding, removing and arranging children, shared by the
+/// containers that lay them out in lines, such as
+/// [HContainer](super::HContainer) and [FlowContainer](super::FlowContainer).
+/// They expect `children`, `align`, `justify` and `needs_layout` fields like
+/// theirs.
+///
+/// Children taken out of the container release focus held anywhere in them,
+/// as input can't reach them any more.
+macro_rules! child_list_methods {
+    () => {
+        pub fn add_child(&mut self, child: Wrap<T>) -> &mut Self {
+            FrameworkState::request_load();
+            self.children.push((child, ChildState::new()));
+            self
+        }
+
+        /// Inserts a child at `index`, shifting the ones after it along.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index > len`.
+        pub fn insert_child(
+            &mut self,
+            index: usize,
+            child: Wrap<T>,
+        ) -> &mut Self {
+            FrameworkState::request_load();
+            self.children.insert(index, (child, ChildState::new()));
+            self.needs_layout = true;
+            self
+        }
+
+        /// Removes and returns the child at `index`, releasing focus held in
+        /// it.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is out of bounds.
+        pub fn remove_child(&mut self, index: usize) -> Wrap<T> {
+            self.needs_layout = true;
+            let (child, _) = self.children.remove(index);
+            FrameworkState::release_focus_within(&child);
+            child
+        }
+
+        /// Replaces the child at `index`, returning the old one after
+        /// releasing focus held in it.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is out of bounds.
+        pub fn replace_child(
+            &mut self,
+            index: usize,
+            child: Wrap<T>,
+        ) -> Wrap<T> {
+            FrameworkState::request_load();
+            self.needs_layout = true;
+            let (old, _) = std::mem::replace(
+                &mut self.children[index],
+                (child, ChildState::new()),
+            );
+            FrameworkState::release_focus_within(&old);
+            old
+        }
+
+        /// Moves the child at `from` so that it ends up at `to`, keeping its
+        /// state. No loading is needed, as it's the same child.
+        ///
+        /// # Panics
+        ///
+        /// Panics if either index is out of bounds.
+        pub fn move_child(&mut self, from: usize, to: usize) {
+            let child = self.children.remove(from);
+            self.children.insert(to, child);
+            self.needs_layout = true;
+        }
+
+        /// Keeps only the children for which `f` returns true, releasing focus
+        /// held in the others.
+        pub fn retain_children<F: FnMut(&Wrap<T>) -> bool>(
+            &mut self,
+            mut f: F,
+        ) {
+            let len = self.children.len();
+            self.children.retain(|(child, _)| {
+                let keep = f(child);
+                if !keep {
+                    FrameworkState::release_focus_within(child);
+                }
+                keep
+            });
+            self.needs_layout |= self.children.len() != len;
+        }
+
+        /// Removes every child, releasing focus held in any of them.
+        pub fn clear_children(&mut self) {
+            self.needs_layout |= !self.children.is_empty();
+            for (child, _) in &self.children {
+                FrameworkState::release_focus_within(child);
+            }
+            self.children.clear();
+        }
+
+        pub fn child(&self, index: usize) -> Option<&Wrap<T>> {
+            self.children.get(index).map(|(child, _)| child)
+        }
+
+        pub fn child_mut(&mut self, index: usize) -> Option<&mut Wrap<T>> {
+            self.children.get_mut(index).map(|(child, _)| child)
+        }
+
+        pub fn children(&self) -> impl ExactSizeIterator<Item = &Wrap<T>> {
+            self.children.iter().map(|(child, _)| child)
+        }
+
+        pub fn children_mut(
+            &mut self,
+        ) -> impl ExactSizeIterator<Item = &mut Wrap<T>> {
+            self.children.iter_mut().map(|(child, _)| child)
+        }
+
+        /// The index of the child with the given ID, as from [Wrap::id].
+        pub fn position_of(&self, id: ID) -> Option<usize> {
+            self.children.iter().position(|(child, _)| child.id() == id)
+        }
+
+        pub fn len(&self) -> usize {
+            self.children.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.children.is_empty()
+        }
+
+        /// Sets how children are placed across the line they're in, unless set
+        /// for a child with [Self::set_child_align]. Children expanding that
+        /// way fill the line regardless.
+        pub fn set_align(&mut self, align: Align) -> &mut Self {
+            self.align = align;
+            self.needs_layout = true;
+            self
+        }
+
+        /// Sets how leftover space along a line is spread out between its
+        /// children.
+        pub fn set_justify(&mut self, justify: Justify) -> &mut Self {
+            self.justify = justify;
+            self.needs_layout = true;
+            self
+        }
+
+        /// Sets how the child at `index` is placed across its line, or goes
+        /// back to the container's alignment with `None`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is out of bounds.
+        pub fn set_child_align(
+            &mut self,
+            index: usize,
+            align: Option<Align>,
+        ) -> &mut Self {
+            self.children[index].1.align = align;
+            self.needs_layout = true;
+            self
+        }
+    };
+}
+
+pub(crate) use child_list_methods;
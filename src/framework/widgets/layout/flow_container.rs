@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use crate::game::ID;
+use crate::prelude::*;
+
+use super::align::{Align, Justify};
+use super::container::{child_list_methods, ChildState, ContainerSize};
+
+/// Places children left to right, moving on to a new line whenever the
+/// width runs out, much as text wraps.
+///
+/// Like [TextLayoutMode::MinHeight](super::super::TextLayoutMode), its
+/// minimum height is that of its lines at the width it was last given, so
+/// a change in width shows in its layout size a frame later.
+pub struct FlowContainer<T: Widget + ?Sized> {
+    children: Vec<(Wrap<T>, ChildState)>,
+    size: ContainerSize,
+    spacing: scalar,
+    line_spacing: scalar,
+    justify: Justify,
+    align: Align,
+    width: Option<scalar>,
+    height: scalar,
+    needs_layout: bool,
+}
+
+pub type FlowContainerDyn = FlowContainer<dyn Widget>;
+
+impl<T: Widget + ?Sized> FlowContainer<T> {
+    /// Makes a flow container with `spacing` between children on a line and
+    /// `line_spacing` between lines.
+    pub fn new(
+        size: ContainerSize,
+        spacing: Option<scalar>,
+        line_spacing: Option<scalar>,
+    ) -> Wrap<Self> {
+        // `FrameworkState::request_load();` here is not needed, as there are
+        // no children just yet.
+        Self {
+            children: vec![],
+            size,
+            spacing: spacing.unwrap_or(0.0),
+            line_spacing: line_spacing.unwrap_or(0.0),
+            justify: Justify::default(),
+            align: Align::default(),
+            width: None,
+            height: 0.0,
+            needs_layout: false,
+        }
+        .into()
+    }
+
+    child_list_methods!();
+
+    /// The minimum height of the children laid out at the given width, going
+    /// by their sizes as of the last size pass.
+    pub fn height_for_width(&self, width: scalar) -> scalar {
+        self.measure(width)
+    }
+
+    /// Splits the children into lines no wider than `width`. A child wider
+    /// than that gets a line of its own.
+    fn lines(&self, width: scalar) -> Vec<Range<usize>> {
+        let mut lines = vec![];
+        let mut start = 0;
+        let mut line_width = 0.0;
+        for (i, (_, state)) in self.children.iter().enumerate() {
+            let w = state.layout_size.width.min;
+            if i > start && line_width + self.spacing + w > width {
+                lines.push(start..i);
+                start = i;
+                line_width = w;
+            } else if i > start {
+                line_width += self.spacing + w;
+            } else {
+                line_width = w;
+            }
+        }
+        if start < self.children.len() {
+            lines.push(start..self.children.len());
+        }
+        lines
+    }
+
+    fn line_height(&self, line: Range<usize>) -> scalar {
+        self.children[line]
+            .iter()
+            .map(|(_, state)| state.layout_size.height.min)
+            .fold(0.0, scalar::max)
+    }
+
+    /// The height of the children laid out in lines of the given width.
+    fn measure(&self, width: scalar) -> scalar {
+        let lines = self.lines(width);
+        let gaps = lines.len().saturating_sub(1) as scalar * self.line_spacing;
+        lines
+            .into_iter()
+            .map(|l| self.line_height(l))
+            .sum::<scalar>()
+            + gaps
+    }
+
+    fn layout(&mut self, size: Size) {
+        let lines = self.lines(size.width);
+        let mut y = 0.0;
+        for line in lines {
+            let height = self.line_height(line.clone());
+            let used = self.children[line.clone()]
+                .iter()
+                .map(|(_, state)| state.layout_size.width.min)
+                .sum::<scalar>()
+                + (line.len() - 1) as scalar * self.spacing;
+            let left = (size.width - used).max(0.0);
+            let (mut x, gap) = self.justify.distribute(left, line.len());
+            for (child, state) in &mut self.children[line] {
+                let layout_size = state.layout_size;
+                let width = layout_size.width.min.min(size.width);
+                let align = state.align.unwrap_or(self.align);
+                let child_height = align.cross_size(layout_size.height, height);
+                let child_size =
+                    layout_size.fit_aspect(Size::new(width, child_height));
+                let offset = align.cross_offset(child_size.height, height);
+                state.position.set(x, y + offset);
+                x += width + self.spacing + gap;
+                state.maybe_set_size(child, child_size);
+            }
+            y += height + self.line_spacing;
+        }
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for FlowContainer<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        for (child, _) in &mut self.children {
+            child.load(stack);
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        for (child, _) in &mut self.children {
+            child.update();
+        }
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        let c = event.is_consumable();
+        let mut any = false;
+        for (child, state) in self.children.iter_mut().rev() {
+            if let Some(event) =
+                event.reverse_map_position(Matrix::translate(state.position))
+            {
+                if child.input(&event) {
+                    any = true;
+                    if c {
+                        break;
+                    }
+                }
+            }
+        }
+        any
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut children_changed = std::mem::take(&mut self.needs_layout);
+        let mut width_min = 0.0f32;
+        for (child, state) in &mut self.children {
+            let (size, s, c) = state.size(child);
+            children_changed |= s || c;
+            width_min = width_min.max(size.width.min);
+        }
+
+        // Until it's been given a width, everything goes on one line.
+        let width = self.width.unwrap_or(scalar::INFINITY);
+        self.height = self.measure(width);
+        (
+            self.size.apply(&LayoutSize::min(width_min, self.height)),
+            children_changed,
+        )
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        if self.width != Some(size.width) {
+            self.width = Some(size.width);
+            // Report the new height on the next size pass.
+            self.needs_layout |= self.measure(size.width) != self.height;
+        }
+        self.layout(size);
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        for (child, state) in &mut self.children {
+            let m = Matrix::translate(state.position);
+            canvas.save();
+            canvas.concat(&m);
+            child.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for (child, _) in &self.children {
+            f(child);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        let width = self.width.unwrap_or(scalar::INFINITY);
+        vec![("lines", self.lines(width).len().to_string())]
+    }
+}
@@ -55,4 +55,8 @@ impl<T: Widget + ?Sized> Widget for FullscreenContainer<T> {
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut skia::Canvas) {
         self.child.draw(canvas);
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
 }
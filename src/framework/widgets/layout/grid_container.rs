@@ -0,0 +1,410 @@
+use crate::prelude::*;
+
+use super::align::Align;
+use super::container::{ChildState, ContainerSize};
+
+/// The size of a row or column in a [GridContainer].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Track {
+    /// A fixed number of logical pixels.
+    Fixed(scalar),
+    /// As small as the largest minimum size of the children in it.
+    Auto,
+    /// Like [Track::Auto], but also takes a share of the leftover space,
+    /// weighted by the given factor, much like [LayoutDimension::expand].
+    Fraction(scalar),
+}
+
+impl Track {
+    fn fraction(self) -> scalar {
+        match self {
+            Track::Fraction(f) => f,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Where a child goes in a [GridContainer].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GridCell {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+    /// Overrides the container's horizontal alignment for this child.
+    pub align_x: Option<Align>,
+    /// Overrides the container's vertical alignment for this child.
+    pub align_y: Option<Align>,
+}
+
+impl GridCell {
+    pub fn new(column: usize, row: usize) -> Self {
+        Self {
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+            align_x: None,
+            align_y: None,
+        }
+    }
+
+    /// Makes the cell cover several columns and rows, at least one of each.
+    pub fn span(mut self, columns: usize, rows: usize) -> Self {
+        self.column_span = columns.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
+
+    pub fn align(mut self, x: Align, y: Align) -> Self {
+        self.align_x = Some(x);
+        self.align_y = Some(y);
+        self
+    }
+}
+
+/// The tracks along one axis of a grid.
+struct Axis {
+    tracks: Vec<Track>,
+    gap: scalar,
+    align: Align,
+    mins: Vec<scalar>,
+    offsets: Vec<scalar>,
+    sizes: Vec<scalar>,
+}
+
+impl Axis {
+    fn new(tracks: Vec<Track>) -> Self {
+        Self {
+            tracks,
+            gap: 0.0,
+            align: Align::default(),
+            mins: vec![],
+            offsets: vec![],
+            sizes: vec![],
+        }
+    }
+
+    /// Tracks past the ones given are automatic.
+    fn track(&self, i: usize) -> Track {
+        self.tracks.get(i).copied().unwrap_or(Track::Auto)
+    }
+
+    fn gaps(&self, count: usize) -> scalar {
+        count.saturating_sub(1) as scalar * self.gap
+    }
+
+    /// Works out the minimum size of `count` tracks holding children that
+    /// start at a track, span some tracks and have a minimum size.
+    fn measure(&mut self, count: usize, items: &[(usize, usize, scalar)]) {
+        let mut mins: Vec<scalar> = (0..count)
+            .map(|i| match self.track(i) {
+                Track::Fixed(size) => size,
+                _ => 0.0,
+            })
+            .collect();
+        for &(start, _, min) in items.iter().filter(|i| i.1 == 1) {
+            if let Track::Auto | Track::Fraction(_) = self.track(start) {
+                mins[start] = mins[start].max(min);
+            }
+        }
+
+        // Children spanning several tracks grow fractional tracks if there
+        // are any, or else automatic ones.
+        for &(start, span, min) in items.iter().filter(|i| i.1 > 1) {
+            let range = start..start + span;
+            let current =
+                mins[range.clone()].iter().sum::<scalar>() + self.gaps(span);
+            let extra = min - current;
+            if extra <= 0.0 {
+                continue;
+            }
+            let fractions: scalar =
+                range.clone().map(|i| self.track(i).fraction()).sum();
+            let autos = range
+                .clone()
+                .filter(|&i| self.track(i) == Track::Auto)
+                .count();
+            for i in range {
+                mins[i] += match self.track(i) {
+                    Track::Fraction(f) if fractions > 0.0 => {
+                        extra * f / fractions
+                    }
+                    Track::Auto if fractions <= 0.0 => extra / autos as scalar,
+                    _ => 0.0,
+                };
+            }
+        }
+        self.mins = mins;
+    }
+
+    fn min(&self) -> scalar {
+        self.mins.iter().sum::<scalar>() + self.gaps(self.mins.len())
+    }
+
+    /// Sizes and places the tracks in `space`, handing leftover space to
+    /// fractional tracks.
+    fn layout(&mut self, space: scalar) {
+        let count = self.mins.len();
+        let left = (space - self.min()).max(0.0);
+        let fractions: scalar =
+            (0..count).map(|i| self.track(i).fraction()).sum();
+        self.sizes = (0..count)
+            .map(|i| match self.track(i) {
+                Track::Fraction(f) if fractions > 0.0 => {
+                    self.mins[i] + left * f / fractions
+                }
+                _ => self.mins[i],
+            })
+            .collect();
+        let mut offset = 0.0;
+        self.offsets = self
+            .sizes
+            .iter()
+            .map(|size| {
+                let start = offset;
+                offset += size + self.gap;
+                start
+            })
+            .collect();
+    }
+
+    /// The start and length of the area covering `span` tracks from `start`.
+    fn area(&self, start: usize, span: usize) -> (scalar, scalar) {
+        let end = start + span - 1;
+        let from = self.offsets[start];
+        (from, self.offsets[end] + self.sizes[end] - from)
+    }
+}
+
+/// Lays out children in rows and columns, lining up across both.
+///
+/// Children that don't expand are placed in their cells by alignment, with
+/// baseline alignment placing them at the start.
+///
+/// ```ignore
+/// let mut grid = GridContainer::new(
+///     vec![Track::Auto, Track::Fraction(1.0)],
+///     vec![Track::Auto; 3],
+///     ContainerSize::ZERO.expand_width(),
+/// );
+/// grid.inner_mut()
+///     .set_gaps(12.0, 8.0)
+///     .add_child(name_label, GridCell::new(0, 0))
+///     .add_child(name_edit, GridCell::new(1, 0))
+///     .add_child(save_button, GridCell::new(0, 2).span(2, 1));
+/// ```
+pub struct GridContainer<T: Widget + ?Sized> {
+    children: Vec<(Wrap<T>, GridCell, ChildState)>,
+    columns: Axis,
+    rows: Axis,
+    size: ContainerSize,
+    needs_layout: bool,
+}
+
+pub type GridContainerDyn = GridContainer<dyn Widget>;
+
+impl<T: Widget + ?Sized> GridContainer<T> {
+    /// Makes a grid with the given column and row tracks. Children placed
+    /// past the last track add automatic tracks as needed.
+    pub fn new(
+        columns: Vec<Track>,
+        rows: Vec<Track>,
+        size: ContainerSize,
+    ) -> Wrap<Self> {
+        // `FrameworkState::request_load();` here is not needed, as there are
+        // no children just yet.
+        Self {
+            children: vec![],
+            columns: Axis::new(columns),
+            rows: Axis::new(rows),
+            size,
+            needs_layout: false,
+        }
+        .into()
+    }
+
+    pub fn add_child(&mut self, child: Wrap<T>, cell: GridCell) -> &mut Self {
+        FrameworkState::request_load();
+        let cell = cell.span(cell.column_span, cell.row_span);
+        self.children.push((child, cell, ChildState::new()));
+        self.needs_layout = true;
+        self
+    }
+
+    /// Removes and returns the child at `index`, in the order added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_child(&mut self, index: usize) -> Wrap<T> {
+        self.needs_layout = true;
+        self.children.remove(index).0
+    }
+
+    pub fn clear_children(&mut self) {
+        self.needs_layout |= !self.children.is_empty();
+        self.children.clear();
+    }
+
+    /// Moves the child at `index` to another cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_cell(&mut self, index: usize, cell: GridCell) {
+        self.children[index].1 = cell.span(cell.column_span, cell.row_span);
+        self.needs_layout = true;
+    }
+
+    pub fn cell(&self, index: usize) -> Option<GridCell> {
+        self.children.get(index).map(|(_, cell, _)| *cell)
+    }
+
+    pub fn children(&self) -> impl ExactSizeIterator<Item = &Wrap<T>> {
+        self.children.iter().map(|(child, _, _)| child)
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn set_columns(&mut self, columns: Vec<Track>) -> &mut Self {
+        self.columns.tracks = columns;
+        self.needs_layout = true;
+        self
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<Track>) -> &mut Self {
+        self.rows.tracks = rows;
+        self.needs_layout = true;
+        self
+    }
+
+    /// Sets the space between columns and between rows.
+    pub fn set_gaps(
+        &mut self,
+        column_gap: scalar,
+        row_gap: scalar,
+    ) -> &mut Self {
+        self.columns.gap = column_gap;
+        self.rows.gap = row_gap;
+        self.needs_layout = true;
+        self
+    }
+
+    /// Sets how children are placed in their cells, unless set in their
+    /// [GridCell]. Children expanding in a direction fill their cell in
+    /// that direction regardless.
+    pub fn set_align(&mut self, x: Align, y: Align) -> &mut Self {
+        self.columns.align = x;
+        self.rows.align = y;
+        self.needs_layout = true;
+        self
+    }
+
+    fn layout(&mut self, size: Size) {
+        self.columns.layout(size.width);
+        self.rows.layout(size.height);
+        for (child, cell, state) in &mut self.children {
+            let (x, width) = self.columns.area(cell.column, cell.column_span);
+            let (y, height) = self.rows.area(cell.row, cell.row_span);
+            let align_x = cell.align_x.unwrap_or(self.columns.align);
+            let align_y = cell.align_y.unwrap_or(self.rows.align);
+            let layout_size = state.layout_size;
+            let child_size = layout_size.fit_aspect(Size::new(
+                align_x.cross_size(layout_size.width, width),
+                align_y.cross_size(layout_size.height, height),
+            ));
+            state.position.set(
+                x + align_x.cross_offset(child_size.width, width),
+                y + align_y.cross_offset(child_size.height, height),
+            );
+            state.maybe_set_size(child, child_size);
+        }
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for GridContainer<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        for (child, _, _) in &mut self.children {
+            child.load(stack);
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        for (child, _, _) in &mut self.children {
+            child.update();
+        }
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        let c = event.is_consumable();
+        let mut any = false;
+        for (child, _, state) in self.children.iter_mut().rev() {
+            if let Some(event) =
+                event.reverse_map_position(Matrix::translate(state.position))
+            {
+                if child.input(&event) {
+                    any = true;
+                    if c {
+                        break;
+                    }
+                }
+            }
+        }
+        any
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut children_changed = std::mem::take(&mut self.needs_layout);
+        let mut column_items = Vec::with_capacity(self.children.len());
+        let mut row_items = Vec::with_capacity(self.children.len());
+        let mut column_count = self.columns.tracks.len();
+        let mut row_count = self.rows.tracks.len();
+        for (child, cell, state) in &mut self.children {
+            let (size, s, c) = state.size(child);
+            children_changed |= s || c;
+            column_items.push((cell.column, cell.column_span, size.width.min));
+            row_items.push((cell.row, cell.row_span, size.height.min));
+            column_count = column_count.max(cell.column + cell.column_span);
+            row_count = row_count.max(cell.row + cell.row_span);
+        }
+        self.columns.measure(column_count, &column_items);
+        self.rows.measure(row_count, &row_items);
+
+        let min = LayoutSize::min(self.columns.min(), self.rows.min());
+        (self.size.apply(&min), children_changed)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.layout(size);
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        for (child, _, state) in &mut self.children {
+            let m = Matrix::translate(state.position);
+            canvas.save();
+            canvas.concat(&m);
+            child.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for (child, _, _) in &self.children {
+            f(child);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("columns", format!("{:?}", self.columns.sizes)),
+            ("rows", format!("{:?}", self.rows.sizes)),
+        ]
+    }
+}
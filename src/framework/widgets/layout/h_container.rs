@@ -1,12 +1,19 @@
+use crate::game::ID;
 use crate::prelude::*;
 
-use super::container::{ChildState, ContainerSize};
+use super::align::{Align, Justify};
+use super::container::{
+    child_list_methods, distribute, ChildState, ContainerSize,
+};
 
 pub struct HContainer<T: Widget + ?Sized> {
     children: Vec<(Wrap<T>, ChildState)>,
     size: ContainerSize,
     spacing: scalar,
+    align: Align,
+    justify: Justify,
     sizes_changed: bool,
+    needs_layout: bool,
 }
 
 pub type HContainerDyn = HContainer<dyn Widget>;
@@ -19,45 +26,65 @@ impl<T: Widget + ?Sized> HContainer<T> {
             children: vec![],
             size,
             spacing: spacing.unwrap_or(0.0),
+            align: Align::default(),
+            justify: Justify::default(),
             sizes_changed: false,
+            needs_layout: false,
         }
         .into()
     }
 
-    pub fn add_child(&mut self, child: Wrap<T>) -> &mut Self {
-        FrameworkState::request_load();
-        self.children.push((child, ChildState::new()));
-        self
-    }
+    child_list_methods!();
 
     fn layout(&mut self, _state: &mut WidgetState, size: Size) {
-        let total_space = size.width;
-
-        let mut min = self.preoccupied();
-        let mut expand = 0.0f32;
-
-        for (_, state) in &self.children {
-            min += state.layout_size.width.min;
-            if let Some(e) = state.layout_size.width.expand {
-                expand += e;
-            }
+        let space = (size.width - self.preoccupied()).max(0.0);
+        let dims: Vec<_> = self
+            .children
+            .iter()
+            .map(|(_, state)| state.layout_size.width)
+            .collect();
+        let sizes = distribute(&dims, space);
+
+        let space_left = (space - sizes.iter().sum::<scalar>()).max(0.0);
+        let (mut offset, gap) =
+            self.justify.distribute(space_left, self.children.len());
+        for ((child, state), width) in self.children.iter_mut().zip(sizes) {
+            let align = state.align.unwrap_or(self.align);
+            let height =
+                align.cross_size(state.layout_size.height, size.height);
+            let child_size =
+                state.layout_size.fit_aspect(Size::new(width, height));
+            let y = align.cross_offset(child_size.height, size.height);
+            state.position.set(offset, y);
+            offset += width + self.spacing + gap;
+            state.maybe_set_size(child, child_size);
         }
+        self.align_baselines();
+    }
 
-        let space_left = (total_space - min).max(0.0);
-        let mut offset = 0.0;
-        for (child, state) in &mut self.children {
-            let mut width = state.layout_size.width.min;
-            if let Some(e) = state.layout_size.width.expand {
-                width += space_left * e / expand;
+    /// Moves children aligned by baseline down so that their baselines line
+    /// up with the lowest one.
+    fn align_baselines(&mut self) {
+        let align = self.align;
+        let baselines: Vec<_> = self
+            .children
+            .iter()
+            .map(|(child, state)| match state.align.unwrap_or(align) {
+                Align::Baseline => child.baseline(),
+                _ => None,
+            })
+            .collect();
+        let mut line: Option<scalar> = None;
+        for &b in baselines.iter().flatten() {
+            line = Some(line.map_or(b, |line| line.max(b)));
+        }
+        if let Some(line) = line {
+            let children = self.children.iter_mut().zip(baselines);
+            for ((_, state), b) in children {
+                if let Some(b) = b {
+                    state.position.y = line - b;
+                }
             }
-            state.position.set(offset, 0.0);
-            offset += width + self.spacing;
-            let height = if state.layout_size.height.expand.is_some() {
-                size.height
-            } else {
-                state.layout_size.height.min.min(size.height)
-            };
-            state.maybe_set_size(child, Size::new(width, height));
         }
     }
 
@@ -102,15 +129,31 @@ impl<T: Widget + ?Sized> Widget for HContainer<T> {
         let mut height_min = 0.0f32;
 
         self.sizes_changed = false;
-        let mut children_changed = false;
-
+        // Changes to the children or how they're arranged need laying out
+        // again, even if the total size stays the same.
+        let mut children_changed = std::mem::take(&mut self.needs_layout);
+
+        // Children lined up by baseline are shifted down, so they need room
+        // for the tallest part above the baseline plus the tallest below it.
+        // Baselines are as of the last layout.
+        let (mut ascent, mut descent) = (0.0f32, 0.0f32);
+        let align = self.align;
         for (child, state) in &mut self.children {
             let (size, s, c) = state.size(child);
             self.sizes_changed |= s;
             children_changed |= c;
             width_min += size.width.min;
             height_min = height_min.max(size.height.min);
+            let baseline = match state.align.unwrap_or(align) {
+                Align::Baseline => child.baseline(),
+                _ => None,
+            };
+            if let Some(b) = baseline {
+                ascent = ascent.max(b);
+                descent = descent.max(size.height.min - b);
+            }
         }
+        height_min = height_min.max(ascent + descent);
 
         (
             self.size.apply(&LayoutSize::min(width_min, height_min)),
@@ -122,6 +165,12 @@ impl<T: Widget + ?Sized> Widget for HContainer<T> {
         self.layout(state, size);
     }
 
+    fn baseline(&self, _state: &WidgetState) -> Option<scalar> {
+        self.children.iter().find_map(|(child, state)| {
+            child.baseline().map(|b| b + state.position.y)
+        })
+    }
+
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
         for (child, state) in &mut self.children {
             let m = Matrix::translate(state.position);
@@ -131,4 +180,10 @@ impl<T: Widget + ?Sized> Widget for HContainer<T> {
             canvas.restore();
         }
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for (child, _) in &self.children {
+            f(child);
+        }
+    }
 }
@@ -44,6 +44,12 @@ impl<T: Widget + ?Sized> Widget for MarginContainer<T> {
         let margin_size = self.margin.size();
         child_size.width.min += margin_size.width;
         child_size.height.min += margin_size.height;
+        if let Some(max) = &mut child_size.width.max {
+            *max += margin_size.width;
+        }
+        if let Some(max) = &mut child_size.height.max {
+            *max += margin_size.height;
+        }
         (child_size, changed)
     }
 
@@ -63,6 +69,18 @@ impl<T: Widget + ?Sized> Widget for MarginContainer<T> {
         self.child.draw(canvas);
         canvas.restore();
     }
+
+    fn baseline(&self, _state: &WidgetState) -> Option<scalar> {
+        self.child.baseline().map(|b| b + self.margin.top)
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("margin", format!("{:?}", self.margin))]
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
@@ -0,0 +1,526 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::super::DrawProps;
+use crate::game::ID;
+use crate::prelude::*;
+
+/// A widget that can be shown by a [ScreenStack].
+///
+/// The lifecycle callbacks are called as the stack changes, before any
+/// transition plays.
+pub trait Screen: Widget {
+    /// The screen was pushed onto the stack, or replaced the one on top.
+    fn entering(&mut self) {}
+
+    /// The screen was popped off the stack, or replaced by another one.
+    fn exiting(&mut self) {}
+
+    /// Another screen was pushed on top of this one.
+    fn suspending(&mut self) {}
+
+    /// The screen on top of this one was popped, making it active again.
+    fn resuming(&mut self) {}
+}
+
+/// How screens come and go.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TransitionKind {
+    /// Screens change instantly.
+    Cut,
+    Fade,
+    /// New screens slide in from the given direction, a unit vector in
+    /// fractions of the stack's size. For example, `(1, 0)` slides them in
+    /// from the right. Popped screens slide back out the same way.
+    Slide(Vector),
+    /// New screens grow from the given scale while fading in.
+    Scale(scalar),
+}
+
+/// A transition between screens, see [TransitionKind].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    pub duration: Duration,
+    pub easing: EasingFn,
+}
+
+impl Transition {
+    pub const CUT: Self = Self {
+        kind: TransitionKind::Cut,
+        duration: Duration::from_secs(0),
+        easing: EasingFn::Linear,
+    };
+
+    pub fn new(kind: TransitionKind, duration: Duration) -> Self {
+        Self {
+            kind,
+            duration,
+            easing: EasingFn::OutCubic,
+        }
+    }
+
+    pub fn fade(duration: Duration) -> Self {
+        Self::new(TransitionKind::Fade, duration)
+    }
+
+    pub fn slide(direction: Vector, duration: Duration) -> Self {
+        Self::new(TransitionKind::Slide(direction), duration)
+    }
+
+    pub fn scale(from: scalar, duration: Duration) -> Self {
+        Self::new(TransitionKind::Scale(from), duration)
+    }
+
+    pub fn with_easing(mut self, easing: EasingFn) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Sets up `props` for a screen `shown` of the way in, from 0 to 1.
+    /// `incoming` is whether the screen moves in the same direction as
+    /// screens being pushed.
+    fn apply(
+        &self,
+        props: &mut DrawProps,
+        size: Size,
+        shown: scalar,
+        incoming: bool,
+    ) {
+        match self.kind {
+            TransitionKind::Cut => {}
+            TransitionKind::Fade => props.alpha = shown,
+            TransitionKind::Slide(direction) => {
+                let sign = if incoming { 1.0 } else { -1.0 };
+                let hidden = (1.0 - shown) * sign;
+                props.position = Vector::new(
+                    direction.x * size.width * hidden,
+                    direction.y * size.height * hidden,
+                );
+            }
+            TransitionKind::Scale(from) => {
+                let scale = from.lerp(1.0, shown);
+                props.scale = Vector::new(scale, scale);
+                props.alpha = shown;
+            }
+        }
+    }
+
+    /// Puts back whatever [Transition::apply] changed.
+    fn reset(props: &mut DrawProps) {
+        props.alpha = 1.0;
+        props.position = Vector::default();
+        props.scale = Vector::new(1.0, 1.0);
+    }
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::fade(Duration::from_millis(250))
+    }
+}
+
+enum Command {
+    Push(Wrap<dyn Screen>, Option<Transition>),
+    Pop(Option<Transition>),
+    Replace(Wrap<dyn Screen>, Option<Transition>),
+}
+
+/// A handle for changing a [ScreenStack] from anywhere, including from its
+/// own screens. Changes are made on the stack's next update.
+#[derive(Clone)]
+pub struct ScreenNavigator {
+    commands: Rc<RefCell<Vec<Command>>>,
+}
+
+impl ScreenNavigator {
+    pub fn push<T: Screen + 'static>(&self, screen: Wrap<T>) {
+        self.send(Command::Push(screen.into(), None));
+    }
+
+    pub fn push_with<T: Screen + 'static>(
+        &self,
+        screen: Wrap<T>,
+        transition: Transition,
+    ) {
+        self.send(Command::Push(screen.into(), Some(transition)));
+    }
+
+    pub fn pop(&self) {
+        self.send(Command::Pop(None));
+    }
+
+    pub fn pop_with(&self, transition: Transition) {
+        self.send(Command::Pop(Some(transition)));
+    }
+
+    pub fn replace<T: Screen + 'static>(&self, screen: Wrap<T>) {
+        self.send(Command::Replace(screen.into(), None));
+    }
+
+    pub fn replace_with<T: Screen + 'static>(
+        &self,
+        screen: Wrap<T>,
+        transition: Transition,
+    ) {
+        self.send(Command::Replace(screen.into(), Some(transition)));
+    }
+
+    fn send(&self, command: Command) {
+        self.commands.borrow_mut().push(command);
+    }
+}
+
+struct Running {
+    /// The screen going away, if any.
+    from: Option<Wrap<dyn Screen>>,
+    /// Whether the active screen is new, rather than uncovered by a pop.
+    pushing: bool,
+    transition: Transition,
+    start: Option<Duration>,
+}
+
+/// A stack of screens, only the top one of which is active.
+///
+/// Only the active screen gets input and updates, and only it and any
+/// screen transitioning away are drawn. Transitions animate the screens'
+/// alpha, position and scale [DrawProps].
+///
+/// ```ignore
+/// let stack = ScreenStack::new(Transition::slide(
+///     Vector::new(1.0, 0.0),
+///     Duration::from_millis(300),
+/// ));
+/// let navigator = stack.inner().navigator();
+/// navigator.push(MainMenu::new(navigator.clone()));
+/// ```
+pub struct ScreenStack {
+    /// The transition used when none is given.
+    pub transition: Transition,
+    screens: Vec<Wrap<dyn Screen>>,
+    running: Option<Running>,
+    /// Not the ID of any widget, for taking hover away from everything in a
+    /// screen that stops being active.
+    id: ID,
+    commands: Rc<RefCell<Vec<Command>>>,
+    size: Size,
+    relayout: bool,
+}
+
+impl ScreenStack {
+    pub fn new(transition: Transition) -> Wrap<Self> {
+        Self {
+            transition,
+            screens: vec![],
+            running: None,
+            id: ID::next(),
+            commands: Rc::new(RefCell::new(vec![])),
+            size: Size::new_empty(),
+            relayout: false,
+        }
+        .into()
+    }
+
+    pub fn navigator(&self) -> ScreenNavigator {
+        ScreenNavigator {
+            commands: self.commands.clone(),
+        }
+    }
+
+    /// The active screen.
+    pub fn current(&self) -> Option<&Wrap<dyn Screen>> {
+        self.screens.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Pushes a screen on top, suspending the active one.
+    pub fn push<T: Screen + 'static>(&mut self, screen: Wrap<T>) {
+        self.push_dyn(screen.into(), None);
+    }
+
+    pub fn push_with<T: Screen + 'static>(
+        &mut self,
+        screen: Wrap<T>,
+        transition: Transition,
+    ) {
+        self.push_dyn(screen.into(), Some(transition));
+    }
+
+    /// Pops the active screen, resuming the one below. Returns the popped
+    /// screen, which is still drawn until its transition is done.
+    pub fn pop(&mut self) -> Option<Wrap<dyn Screen>> {
+        self.pop_dyn(None)
+    }
+
+    pub fn pop_with(
+        &mut self,
+        transition: Transition,
+    ) -> Option<Wrap<dyn Screen>> {
+        self.pop_dyn(Some(transition))
+    }
+
+    /// Replaces the active screen, without resuming the one below.
+    pub fn replace<T: Screen + 'static>(
+        &mut self,
+        screen: Wrap<T>,
+    ) -> Option<Wrap<dyn Screen>> {
+        self.replace_dyn(screen.into(), None)
+    }
+
+    pub fn replace_with<T: Screen + 'static>(
+        &mut self,
+        screen: Wrap<T>,
+        transition: Transition,
+    ) -> Option<Wrap<dyn Screen>> {
+        self.replace_dyn(screen.into(), Some(transition))
+    }
+
+    fn push_dyn(
+        &mut self,
+        mut screen: Wrap<dyn Screen>,
+        transition: Option<Transition>,
+    ) {
+        let id = self.id;
+        let from = self.screens.last_mut().map(|s| {
+            s.inner_mut().suspending();
+            Self::deactivate(s, id);
+            s.clone()
+        });
+        screen.inner_mut().entering();
+        self.screens.push(screen);
+        self.start(from, true, transition);
+    }
+
+    fn pop_dyn(
+        &mut self,
+        transition: Option<Transition>,
+    ) -> Option<Wrap<dyn Screen>> {
+        let mut popped = self.screens.pop()?;
+        popped.inner_mut().exiting();
+        Self::deactivate(&mut popped, self.id);
+        if let Some(screen) = self.screens.last_mut() {
+            screen.inner_mut().resuming();
+        }
+        self.start(Some(popped.clone()), false, transition);
+        Some(popped)
+    }
+
+    fn replace_dyn(
+        &mut self,
+        mut screen: Wrap<dyn Screen>,
+        transition: Option<Transition>,
+    ) -> Option<Wrap<dyn Screen>> {
+        let id = self.id;
+        let replaced = self.screens.pop().map(|mut s| {
+            s.inner_mut().exiting();
+            Self::deactivate(&mut s, id);
+            s
+        });
+        screen.inner_mut().entering();
+        self.screens.push(screen);
+        self.start(replaced.clone(), true, transition);
+        replaced
+    }
+
+    /// Takes hover and focus away from anything in `screen`, as it stops
+    /// being active. `id` is the stack's own.
+    fn deactivate(screen: &mut Wrap<dyn Screen>, id: ID) {
+        screen.input(&InputEvent::RemoveHoverExcept(id));
+        FrameworkState::release_focus_within(&*screen);
+    }
+
+    fn start(
+        &mut self,
+        from: Option<Wrap<dyn Screen>>,
+        pushing: bool,
+        transition: Option<Transition>,
+    ) {
+        self.finish();
+        FrameworkState::request_load();
+        self.relayout = true;
+        self.running = Some(Running {
+            from,
+            pushing,
+            transition: transition.unwrap_or(self.transition),
+            start: None,
+        });
+    }
+
+    /// Ends the running transition, if any, putting screens back in place.
+    fn finish(&mut self) {
+        if let Some(running) = self.running.take() {
+            if let Some(from) = &running.from {
+                Transition::reset(&mut from.props_mut());
+            }
+            if let Some(to) = self.screens.last() {
+                Transition::reset(&mut to.props_mut());
+            }
+        }
+    }
+
+    fn run_commands(&mut self) {
+        let commands = std::mem::take(&mut *self.commands.borrow_mut());
+        for command in commands {
+            match command {
+                Command::Push(screen, t) => self.push_dyn(screen, t),
+                Command::Pop(t) => {
+                    self.pop_dyn(t);
+                }
+                Command::Replace(screen, t) => {
+                    self.replace_dyn(screen, t);
+                }
+            }
+        }
+    }
+
+    /// Advances the running transition, returning whether it's done.
+    fn animate(&mut self, now: Duration) -> bool {
+        let running = match &mut self.running {
+            Some(running) => running,
+            None => return true,
+        };
+        let start = *running.start.get_or_insert(now);
+        let t = now.checked_sub(start).unwrap_or_default();
+        let duration = running.transition.duration;
+        if t >= duration {
+            return true;
+        }
+        let progress = t.as_secs_f32() / duration.as_secs_f32();
+        let shown = running.transition.easing.apply(progress);
+        let (transition, pushing) = (running.transition, running.pushing);
+        if let Some(from) = &running.from {
+            let props = &mut from.props_mut();
+            transition.apply(props, self.size, 1.0 - shown, !pushing);
+        }
+        if let Some(to) = self.screens.last() {
+            let props = &mut to.props_mut();
+            transition.apply(props, self.size, shown, pushing);
+        }
+        false
+    }
+}
+
+impl Widget for ScreenStack {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        for screen in &mut self.screens {
+            screen.load(stack);
+        }
+        if let Some(Running {
+            from: Some(from), ..
+        }) = &mut self.running
+        {
+            from.load(stack);
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.run_commands();
+        if let Some(screen) = self.screens.last_mut() {
+            screen.update();
+        }
+        if let Some(Running {
+            from: Some(from), ..
+        }) = &mut self.running
+        {
+            from.update();
+        }
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        match self.screens.last_mut() {
+            Some(screen) => screen.input(event),
+            None => false,
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut changed = std::mem::take(&mut self.relayout);
+        let mut size = LayoutSize::ZERO;
+        if let Some(screen) = self.screens.last_mut() {
+            let (s, c) = screen.size();
+            size = s;
+            changed |= c;
+        }
+        (size.expand_width().expand_height(), changed)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        if let Some(screen) = self.screens.last_mut() {
+            screen.set_size(size);
+        }
+        if let Some(Running {
+            from: Some(from), ..
+        }) = &mut self.running
+        {
+            from.set_size(size);
+        }
+    }
+
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
+        if self.animate(state.elapsed()) {
+            self.finish();
+        }
+        let from = match &mut self.running {
+            Some(Running {
+                from: Some(from),
+                pushing,
+                ..
+            }) => Some((from, *pushing)),
+            _ => None,
+        };
+        let to = self.screens.last_mut();
+        match from {
+            // Pushed screens go on top, popped ones stay on top on their
+            // way out.
+            Some((from, true)) => {
+                from.draw(canvas);
+                if let Some(to) = to {
+                    to.draw(canvas);
+                }
+            }
+            Some((from, false)) => {
+                if let Some(to) = to {
+                    to.draw(canvas);
+                }
+                from.draw(canvas);
+            }
+            None => {
+                if let Some(to) = to {
+                    to.draw(canvas);
+                }
+            }
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        if let Some(Running {
+            from: Some(from), ..
+        }) = &self.running
+        {
+            f(from);
+        }
+        for screen in &self.screens {
+            f(screen);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("screens", self.screens.len().to_string()),
+            ("transitioning", self.is_transitioning().to_string()),
+        ]
+    }
+}
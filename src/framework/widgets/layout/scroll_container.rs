@@ -1,57 +1,529 @@
+use std::time::Duration;
+
+use super::super::{Spring, TreeNode};
+use super::container::Axes;
+use super::scroll_offset::{
+    clamp_offset, page_key, reveal, ScrollOffset, LINE,
+};
+use crate::game::ID;
 use crate::prelude::*;
+use skia::Point;
+
+/// How a [ScrollContainer] shows its scrollbars.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Scrollbars {
+    Hidden,
+    /// Drawn over the edge of the content.
+    Overlay,
+    /// Drawn in space kept for them beside the content, whether or not
+    /// there's anything to scroll.
+    Gutter,
+}
+
+impl Default for Scrollbars {
+    fn default() -> Self {
+        Self::Hidden
+    }
+}
+
+/// A scrollbar thumb being dragged.
+#[derive(Copy, Clone)]
+struct Drag {
+    vertical: bool,
+    /// Where the pointer grabbed the thumb, from the thumb's start.
+    grab: scalar,
+}
+
+/// The content being dragged with the pointer.
+struct Pan {
+    start: Point,
+    /// The scroll offset when the pointer went down.
+    origin: Vector,
+    /// Whether the pointer has moved far enough to count as a drag rather
+    /// than a click.
+    dragging: bool,
+    /// Recent pointer positions, for the velocity on release.
+    samples: Vec<(Duration, Point)>,
+}
 
 pub struct ScrollContainer<T: Widget + ?Sized> {
     child: Wrap<T>,
-    offset: scalar,
-    target_offset: scalar,
+    /// How far the content is scrolled, from the top left.
+    offset: ScrollOffset<Vector>,
+    axes: Axes,
+    scrollbars: Scrollbars,
+    scrollbar_paint: Paint,
+    auto_hide: Option<Duration>,
+    last_activity: Duration,
+    drag: Option<Drag>,
+    kinetic: bool,
+    pan: Option<Pan>,
+    fling: Option<Vector>,
+    hovered: bool,
     size: Size,
     layout_size: LayoutSize,
     child_size: Size,
     child_layout_size: LayoutSize,
     matrix: Matrix,
+    screen_matrix: Matrix,
 }
 
 impl<T: Widget + ?Sized> ScrollContainer<T> {
+    const BAR_THICKNESS: scalar = 8.0;
+    const MIN_THUMB: scalar = 24.0;
+    const FADE: Duration = Duration::from_millis(200);
+    /// How far, in logical pixels, the pointer has to move before a press
+    /// becomes a drag.
+    const DRAG_THRESHOLD: scalar = 8.0;
+    /// How long back pointer positions count towards the fling velocity.
+    const FLING_WINDOW: Duration = Duration::from_millis(100);
+    /// How quickly flings slow down, per second.
+    const FRICTION: scalar = 4.0;
+    /// The speed, in logical pixels per second, below which flings stop.
+    const MIN_FLING: scalar = 20.0;
+
+    /// Makes a container scrolling vertically, without scrollbars.
     pub fn new(child: Wrap<T>, size: LayoutSize) -> Wrap<Self> {
         FrameworkState::request_load();
         Self {
             child,
-            offset: 0.0,
-            target_offset: 0.0,
+            offset: ScrollOffset::new(),
+            axes: Axes::Y,
+            scrollbars: Scrollbars::Hidden,
+            scrollbar_paint: Paint::new_color4f(0.5, 0.5, 0.5, 0.6)
+                .anti_alias(),
+            auto_hide: None,
+            last_activity: Duration::default(),
+            drag: None,
+            kinetic: false,
+            pan: None,
+            fling: None,
+            hovered: false,
             size: Size::default(),
             layout_size: size,
             child_size: Size::default(),
             child_layout_size: LayoutSize::ZERO,
             matrix: Matrix::default(),
+            screen_matrix: Matrix::default(),
         }
         .into()
     }
 
-    fn scroll_lines(&mut self, i: scalar) {
-        let offset = i * 50.0;
-        self.target_offset += offset;
-        self.rescroll();
+    /// The spring smoothing out scrolling, for tuning how it feels.
+    pub fn spring_mut(&mut self) -> &mut Spring<Vector> {
+        self.offset.spring_mut()
     }
 
-    fn scroll_pixels(&mut self, p: scalar) {
-        self.target_offset += p;
+    /// Sets which ways the content scrolls. With [Axes::None], it doesn't
+    /// scroll at all, and is just clipped.
+    pub fn set_axes(&mut self, axes: Axes) -> &mut Self {
+        self.axes = axes;
         self.rescroll();
+        self
+    }
+
+    pub fn set_scrollbars(
+        &mut self,
+        scrollbars: Scrollbars,
+        paint: Paint,
+    ) -> &mut Self {
+        self.scrollbars = scrollbars;
+        self.scrollbar_paint = paint;
+        self
+    }
+
+    /// Lets the content be dragged around with the pointer, carrying on
+    /// with the pointer's speed when let go, and stretching past the edges
+    /// before springing back.
+    ///
+    /// Presses only become drags once the pointer moves a little, so
+    /// children like buttons can still be clicked. Once a drag starts,
+    /// children stop getting the pointer until it's released.
+    pub fn set_kinetic(&mut self, kinetic: bool) -> &mut Self {
+        self.kinetic = kinetic;
+        if !kinetic {
+            self.pan = None;
+        }
+        self
+    }
+
+    /// Fades scrollbars out once they've gone unused for `delay`, or never
+    /// with `None`.
+    pub fn set_auto_hide(&mut self, delay: Option<Duration>) -> &mut Self {
+        self.auto_hide = delay;
+        self
+    }
+
+    /// How far the content is scrolled, or is being scrolled to.
+    pub fn scroll_offset(&self) -> Vector {
+        self.offset.target()
+    }
+
+    /// The furthest the content can be scrolled.
+    pub fn max_offset(&self) -> Vector {
+        let viewport = self.viewport();
+        Vector::new(
+            if self.axes.has_x() {
+                (self.child_size.width - viewport.width).max(0.0)
+            } else {
+                0.0
+            },
+            if self.axes.has_y() {
+                (self.child_size.height - viewport.height).max(0.0)
+            } else {
+                0.0
+            },
+        )
+    }
+
+    /// Scrolls smoothly to `offset`, kept within bounds.
+    pub fn scroll_to(&mut self, offset: Vector) {
+        self.fling = None;
+        self.offset.scroll_to(offset, self.max_offset());
+        self.last_activity = State::elapsed_draw();
+    }
+
+    /// Scrolls straight to `offset`, kept within bounds.
+    pub fn jump_to(&mut self, offset: Vector) {
+        self.offset.jump_to(offset, self.max_offset());
+        self.last_activity = State::elapsed_draw();
+    }
+
+    pub fn scroll_by(&mut self, delta: Vector) {
+        self.scroll_to(self.offset.target() + delta);
+    }
+
+    /// Scrolls just far enough to show the widget with the given ID, going
+    /// by where it was last drawn. Returns whether it was found inside this
+    /// container.
+    pub fn scroll_to_widget(&mut self, id: ID) -> bool {
+        let info = match TreeNode::find_info(&self.child, id) {
+            Some(info) => info,
+            None => return false,
+        };
+        let m = Matrix::concat(&self.screen_matrix, &self.matrix);
+        let rect = match m.invert() {
+            Some(inverse) => inverse.map_rect(info.bounds).0,
+            None => return false,
+        };
+        let viewport = self.viewport();
+        let mut offset = self.offset.target();
+        offset.x = reveal(offset.x, viewport.width, rect.left, rect.right);
+        offset.y = reveal(offset.y, viewport.height, rect.top, rect.bottom);
+        self.scroll_to(offset);
+        true
+    }
+
+    /// The size of the area showing content, less any gutters.
+    fn viewport(&self) -> Size {
+        let gutter = match self.scrollbars {
+            Scrollbars::Gutter => Self::BAR_THICKNESS,
+            _ => 0.0,
+        };
+        Size::new(
+            if self.axes.has_y() {
+                (self.size.width - gutter).max(0.0)
+            } else {
+                self.size.width
+            },
+            if self.axes.has_x() {
+                (self.size.height - gutter).max(0.0)
+            } else {
+                self.size.height
+            },
+        )
+    }
+
+    fn scroll_lines(&mut self, lines: Vector) {
+        self.scroll_pixels(lines * LINE);
+    }
+
+    fn scroll_pixels(&mut self, p: Vector) {
+        // Most scroll wheels only go one way, so use them for whichever axis
+        // there is.
+        let p = if self.axes == Axes::X {
+            Vector::new(p.x + p.y, 0.0)
+        } else {
+            p
+        };
+        self.scroll_by(-p);
     }
 
     fn rescroll(&mut self) {
-        let max = (self.child_size.height - self.size.height).max(0.0);
-        self.target_offset = self.target_offset.clamp(-max, 0.0);
+        self.offset.rescroll(self.max_offset());
+        self.last_activity = State::elapsed_draw();
+    }
+
+    fn interpolate_scroll(&mut self, delta: Duration) {
+        if let Some(velocity) = self.fling {
+            self.step_fling(velocity, delta);
+        }
+        let offset = self.offset.update(delta);
+        self.matrix = Matrix::translate(-offset);
+    }
+
+    fn step_fling(&mut self, velocity: Vector, delta: Duration) {
+        let t = delta.as_secs_f32();
+        let offset = self.offset.get() + velocity * t;
+        let velocity = velocity * (-Self::FRICTION * t).exp();
+        let max = self.max_offset();
+        self.offset.set(offset, max);
+        self.last_activity = State::elapsed_draw();
+        if self.offset.target() != offset {
+            // Ran past an edge, so bounce back off it.
+            self.fling = None;
+            self.offset.settle(velocity, max);
+        } else if velocity.length() < Self::MIN_FLING {
+            self.fling = None;
+        } else {
+            self.fling = Some(velocity);
+        }
+    }
+
+    /// Stretches `offset` past either end of `0..=max`, slowing down the
+    /// further it goes.
+    fn elastic(offset: scalar, max: scalar, space: scalar) -> scalar {
+        let stretch = |excess: scalar| {
+            if space > 0.0 {
+                excess * space / (space + excess * 2.0)
+            } else {
+                0.0
+            }
+        };
+        if offset < 0.0 {
+            -stretch(-offset)
+        } else if offset > max {
+            max + stretch(offset - max)
+        } else {
+            offset
+        }
+    }
+
+    fn start_pan(&mut self, p: Point) {
+        // Catching the content stops it where it is.
+        self.fling = None;
+        let offset = self.offset.get();
+        self.offset.set(offset, self.max_offset());
+        self.pan = Some(Pan {
+            start: p,
+            origin: offset,
+            dragging: false,
+            samples: vec![(State::elapsed_draw(), p)],
+        });
+    }
+
+    /// Follows the pointer, returning whether the content is being dragged.
+    fn move_pan(&mut self, state: &mut WidgetState, p: Point) -> bool {
+        let pan = match &mut self.pan {
+            Some(pan) => pan,
+            None => return false,
+        };
+        let now = State::elapsed_draw();
+        pan.samples.push((now, p));
+        pan.samples
+            .retain(|&(time, _)| now - time <= Self::FLING_WINDOW);
+        let started =
+            !pan.dragging && (p - pan.start).length() > Self::DRAG_THRESHOLD;
+        pan.dragging |= started;
+        if !pan.dragging {
+            return false;
+        }
+        let moved = p - pan.start;
+        let origin = pan.origin;
+        if started {
+            self.child.input(&InputEvent::RemoveHoverExcept(state.id()));
+        }
+
+        let max = self.max_offset();
+        let viewport = self.viewport();
+        let mut offset = origin;
+        if self.axes.has_x() {
+            offset.x = Self::elastic(origin.x - moved.x, max.x, viewport.width);
+        }
+        if self.axes.has_y() {
+            offset.y =
+                Self::elastic(origin.y - moved.y, max.y, viewport.height);
+        }
+        self.offset.set(offset, max);
+        self.last_activity = now;
+        true
     }
 
-    fn interpolate_scroll(&mut self) {
-        let delta = self.target_offset - self.offset;
-        if delta.abs() < 0.1 {
-            self.offset = self.target_offset;
+    /// Lets go of the content, returning whether it was being dragged.
+    fn end_pan(&mut self) -> bool {
+        let pan = match self.pan.take() {
+            Some(pan) if pan.dragging => pan,
+            _ => return false,
+        };
+        let mut velocity = match (pan.samples.first(), pan.samples.last()) {
+            (Some(&(t0, p0)), Some(&(t1, p1))) if t1 > t0 => {
+                (p0 - p1) * (1.0 / (t1 - t0).as_secs_f32())
+            }
+            _ => Vector::default(),
+        };
+        if !self.axes.has_x() {
+            velocity.x = 0.0;
+        }
+        if !self.axes.has_y() {
+            velocity.y = 0.0;
+        }
+
+        let offset = self.offset.get();
+        let max = self.max_offset();
+        if clamp_offset(offset, max) != offset {
+            self.offset.settle(velocity, max);
+        } else if velocity.length() >= Self::MIN_FLING {
+            self.fling = Some(velocity);
+        }
+        true
+    }
+
+    fn scroll_key(&mut self, key: Keycode) -> bool {
+        let viewport = self.viewport();
+        let vertical = self.axes.has_y();
+        let mut offset = self.offset.target();
+        let (along, space) = if vertical {
+            (&mut offset.y, viewport.height)
+        } else {
+            (&mut offset.x, viewport.width)
+        };
+        *along = match page_key(key, *along, space) {
+            Some(to) => to,
+            None => return false,
+        };
+        self.scroll_to(offset);
+        true
+    }
+
+    /// The track and thumb of the scrollbar along one axis, if it's shown.
+    fn scrollbar(&self, vertical: bool) -> Option<(Rect, Rect)> {
+        if self.scrollbars == Scrollbars::Hidden {
+            return None;
+        }
+        let viewport = self.viewport();
+        let t = Self::BAR_THICKNESS;
+        let (content, space, track) = if vertical {
+            if !self.axes.has_y() {
+                return None;
+            }
+            let end = if self.axes.has_x() {
+                self.size.height - t
+            } else {
+                self.size.height
+            };
+            let track =
+                Rect::new(self.size.width - t, 0.0, self.size.width, end);
+            (self.child_size.height, viewport.height, track)
+        } else {
+            if !self.axes.has_x() {
+                return None;
+            }
+            let end = if self.axes.has_y() {
+                self.size.width - t
+            } else {
+                self.size.width
+            };
+            let track =
+                Rect::new(0.0, self.size.height - t, end, self.size.height);
+            (self.child_size.width, viewport.width, track)
+        };
+        if content <= space {
+            return None;
+        }
+        let length = if vertical {
+            track.height()
         } else {
-            let t = State::last_update_time_draw().as_secs_f32();
-            self.offset += t * delta * 30.0;
+            track.width()
+        };
+        let thumb = (length * space / content).max(Self::MIN_THUMB).min(length);
+        let max = content - space;
+        let offset = self.offset.get();
+        let scrolled = if vertical { offset.y } else { offset.x };
+        let start = (length - thumb) * (scrolled / max).clamp(0.0, 1.0);
+        let thumb = if vertical {
+            Rect::from_xywh(track.left, track.top + start, t, thumb)
+        } else {
+            Rect::from_xywh(track.left + start, track.top, thumb, t)
+        };
+        Some((track, thumb))
+    }
+
+    /// Moves the dragged thumb so that its grabbed point is under `p`.
+    fn drag_to(&mut self, drag: Drag, p: Point) {
+        let (track, thumb) = match self.scrollbar(drag.vertical) {
+            Some(bar) => bar,
+            None => return,
+        };
+        let max = self.max_offset();
+        let (pos, start, free, max) = if drag.vertical {
+            (p.y, track.top, track.height() - thumb.height(), max.y)
+        } else {
+            (p.x, track.left, track.width() - thumb.width(), max.x)
+        };
+        if free <= 0.0 {
+            return;
         }
-        self.matrix = Matrix::translate((0.0, self.offset));
+        let scrolled = (pos - drag.grab - start) / free * max;
+        let mut offset = self.offset.target();
+        if drag.vertical {
+            offset.y = scrolled;
+        } else {
+            offset.x = scrolled;
+        }
+        self.jump_to(offset);
+    }
+
+    /// Handles a press on either scrollbar, returning whether there was one
+    /// there.
+    fn press_scrollbar(&mut self, state: &mut WidgetState, p: Point) -> bool {
+        for &vertical in &[true, false] {
+            let thumb = match self.scrollbar(vertical) {
+                Some((track, thumb)) if track.contains(p) => thumb,
+                _ => continue,
+            };
+            if thumb.contains(p) {
+                let grab = if vertical {
+                    p.y - thumb.top
+                } else {
+                    p.x - thumb.left
+                };
+                self.drag = Some(Drag { vertical, grab });
+                state.grab_focus();
+            } else {
+                // Clicking the track pages towards the pointer.
+                let viewport = self.viewport();
+                let delta = if vertical {
+                    let page = viewport.height;
+                    Vector::new(0.0, if p.y < thumb.top { -page } else { page })
+                } else {
+                    let page = viewport.width;
+                    Vector::new(
+                        if p.x < thumb.left { -page } else { page },
+                        0.0,
+                    )
+                };
+                self.scroll_by(delta);
+            }
+            return true;
+        }
+        false
+    }
+
+    fn scrollbar_alpha(&self) -> scalar {
+        let delay = match self.auto_hide {
+            Some(delay) if self.drag.is_none() && !self.hovered => delay,
+            _ => return 1.0,
+        };
+        let idle = State::elapsed_draw()
+            .checked_sub(self.last_activity)
+            .unwrap_or_default();
+        let alpha = match idle.checked_sub(delay) {
+            Some(fading) => {
+                1.0 - fading.as_secs_f32() / Self::FADE.as_secs_f32()
+            }
+            None => 1.0,
+        };
+        alpha.clamp(0.0, 1.0)
     }
 }
 
@@ -64,10 +536,42 @@ impl<T: Widget + ?Sized> Widget for ScrollContainer<T> {
         self.child.update();
     }
 
-    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
-        if let Some(p) = event.position() {
-            if event.is_consumable() && !Rect::from_size(self.size).contains(p)
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::MouseMove(p) => {
+                if let Some(drag) = self.drag {
+                    self.drag_to(drag, *p);
+                }
+                self.hovered = Rect::from_size(self.size).contains(*p);
+                if self.hovered && self.scrollbars != Scrollbars::Hidden {
+                    self.last_activity = State::elapsed_draw();
+                }
+                if self.move_pan(state, *p) {
+                    return self.hovered;
+                }
+            }
+            InputEvent::MouseUp(MouseButton::Left, _)
+                if self.drag.is_some() =>
+            {
+                self.drag = None;
+                state.release_focus();
+                return true;
+            }
+            InputEvent::MouseUp(MouseButton::Left, _) if self.end_pan() => {
+                return true;
+            }
+            InputEvent::MouseDown(MouseButton::Left, p)
+                if self.press_scrollbar(state, *p) =>
             {
+                return true;
+            }
+            InputEvent::RemoveHoverExcept(_) => self.hovered = false,
+            _ => {}
+        }
+
+        if let Some(p) = event.position() {
+            let viewport = Rect::from_size(self.viewport());
+            if event.is_consumable() && !viewport.contains(p) {
                 return false;
             }
         }
@@ -76,42 +580,81 @@ impl<T: Widget + ?Sized> Widget for ScrollContainer<T> {
             .reverse_map_position(self.matrix)
             .map(|e| self.child.input(&e) && e.is_consumable())
             .unwrap_or(false);
+        if let InputEvent::MouseDown(MouseButton::Left, p) = event {
+            if self.kinetic && self.axes != Axes::None {
+                self.start_pan(*p);
+                return true;
+            }
+        }
         if !taken {
-            if let InputEvent::MouseScroll(i, _) = event {
-                match i {
-                    ScrollAmount::Lines(Vector { y, .. }) => {
-                        self.scroll_lines(*y);
-                    }
-                    ScrollAmount::Pixels(Vector { y, .. }) => {
-                        self.scroll_pixels(*y);
+            match event {
+                InputEvent::MouseScroll(i, _) if self.axes != Axes::None => {
+                    match i {
+                        ScrollAmount::Lines(p) => self.scroll_lines(*p),
+                        ScrollAmount::Pixels(p) => self.scroll_pixels(*p),
                     }
+                    return true;
                 }
-                return true;
+                InputEvent::KeyDown(key)
+                    if self.hovered && self.axes != Axes::None =>
+                {
+                    return self.scroll_key(*key);
+                }
+                _ => {}
             }
         }
         taken
     }
 
+    fn hover_lost(&mut self, _state: &mut WidgetState) {
+        self.hovered = false;
+    }
+
     fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
         let s = self.child.size();
-        let changed = self.child_layout_size == s.0;
+        let changed = self.child_layout_size != s.0;
         self.child_layout_size = s.0;
         (self.layout_size, changed || s.1)
     }
 
     fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
         self.size = size;
-        self.child_size = self.child_layout_size.layout_one(size);
+        self.child_size = self.child_layout_size.layout_one(self.viewport());
         self.child.set_size(self.child_size);
         self.rescroll();
     }
 
-    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut skia::Canvas) {
-        self.interpolate_scroll();
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut skia::Canvas) {
+        self.screen_matrix = state.matrix();
+        self.interpolate_scroll(state.delta());
         canvas.save();
-        canvas.clip_rect(Rect::from_size(self.size), None, true);
+        canvas.clip_rect(Rect::from_size(self.viewport()), None, true);
         canvas.concat(&self.matrix);
         self.child.draw(canvas);
         canvas.restore();
+
+        let alpha = self.scrollbar_alpha();
+        if alpha > 0.0 {
+            let mut paint = self.scrollbar_paint.clone();
+            paint.set_alpha_f(paint.alpha_f() * alpha);
+            let r = Self::BAR_THICKNESS * 0.5;
+            for &vertical in &[true, false] {
+                if let Some((_, thumb)) = self.scrollbar(vertical) {
+                    canvas.draw_round_rect(thumb, r, r, &paint);
+                }
+            }
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        let offset = self.offset.get();
+        vec![
+            ("offset_x", offset.x.to_string()),
+            ("offset_y", offset.y.to_string()),
+        ]
     }
 }
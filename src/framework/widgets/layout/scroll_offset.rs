@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use super::super::{Spring, SpringValue};
+use crate::prelude::*;
+
+/// How far one line of scrolling goes, as for a notch of a scroll wheel.
+pub(crate) const LINE: scalar = 50.0;
+
+/// A scroll offset, eased by a spring towards where it's being scrolled to.
+///
+/// Shared by the [ScrollContainer](super::ScrollContainer), scrolling along
+/// both axes, and the [VirtualList](super::VirtualList), scrolling along
+/// one. Offsets go from zero up to a maximum given by whatever scrolls, for
+/// each component of `V`.
+pub(crate) struct ScrollOffset<V: SpringValue> {
+    spring: Spring<V>,
+    target: V,
+}
+
+impl<V: SpringValue> ScrollOffset<V> {
+    const STIFFNESS: scalar = 400.0;
+
+    pub(crate) fn new() -> Self {
+        let zero = V::from_components([0.0; 4]);
+        Self {
+            spring: Spring::critically_damped(zero, Self::STIFFNESS),
+            target: zero,
+        }
+    }
+
+    /// The offset as of the last update.
+    pub(crate) fn get(&self) -> V {
+        self.spring.get()
+    }
+
+    /// Where the offset is headed, which is always within bounds.
+    pub(crate) fn target(&self) -> V {
+        self.target
+    }
+
+    pub(crate) fn spring_mut(&mut self) -> &mut Spring<V> {
+        &mut self.spring
+    }
+
+    /// Scrolls smoothly to `offset`, kept within `0..=max`.
+    pub(crate) fn scroll_to(&mut self, offset: V, max: V) {
+        self.target = clamp_offset(offset, max);
+        self.spring.set_target(self.target);
+    }
+
+    /// Brings the target back within `0..=max`, for when the bounds change.
+    pub(crate) fn rescroll(&mut self, max: V) {
+        self.scroll_to(self.target, max);
+    }
+
+    /// Scrolls straight to `offset`, kept within `0..=max`.
+    pub(crate) fn jump_to(&mut self, offset: V, max: V) {
+        self.scroll_to(offset, max);
+        self.spring.set(self.target);
+    }
+
+    /// Puts the offset at `offset` and stops it there, even if that's out of
+    /// bounds, as when content is stretched past its edges. The target is
+    /// still kept within `0..=max`.
+    pub(crate) fn set(&mut self, offset: V, max: V) {
+        self.spring.set(offset);
+        self.target = clamp_offset(offset, max);
+    }
+
+    /// Sends the offset back within `0..=max`, starting off at `velocity`.
+    pub(crate) fn settle(&mut self, velocity: V, max: V) {
+        self.target = clamp_offset(self.spring.get(), max);
+        self.spring.set_velocity(velocity);
+        self.spring.set_target(self.target);
+    }
+
+    /// Moves both the offset and its target by `delta`, keeping any
+    /// scrolling in progress going, for when content above what's on screen
+    /// changes size.
+    pub(crate) fn shift(&mut self, delta: V) {
+        let velocity = self.spring.velocity();
+        self.spring.set(add(self.spring.get(), delta));
+        self.spring.set_velocity(velocity);
+        self.target = add(self.target, delta);
+        self.spring.set_target(self.target);
+    }
+
+    pub(crate) fn update(&mut self, delta: Duration) -> V {
+        self.spring.update(delta)
+    }
+}
+
+/// Keeps every component of `offset` between zero and that of `max`.
+pub(crate) fn clamp_offset<V: SpringValue>(offset: V, max: V) -> V {
+    let mut c = offset.to_components();
+    for (c, max) in c.iter_mut().zip(&max.to_components()) {
+        *c = c.clamp(0.0, max.max(0.0));
+    }
+    V::from_components(c)
+}
+
+fn add<V: SpringValue>(a: V, b: V) -> V {
+    let mut c = a.to_components();
+    for (c, b) in c.iter_mut().zip(&b.to_components()) {
+        *c += b;
+    }
+    V::from_components(c)
+}
+
+/// The offset closest to `offset` at which `start..end` is on screen, with
+/// `space` showing, favouring its start if it doesn't fit.
+pub(crate) fn reveal(
+    offset: scalar,
+    space: scalar,
+    start: scalar,
+    end: scalar,
+) -> scalar {
+    if start < offset {
+        start
+    } else if end > offset + space {
+        (end - space).min(start)
+    } else {
+        offset
+    }
+}
+
+/// Where a paging key scrolls to along one axis, from `offset` with `space`
+/// showing: a page less a line either way for Page Up and Page Down, or
+/// either end for Home and End. The end is infinitely far, to be clamped.
+/// Other keys give `None`.
+pub(crate) fn page_key(
+    key: Keycode,
+    offset: scalar,
+    space: scalar,
+) -> Option<scalar> {
+    let page = (space - LINE).max(LINE);
+    Some(match key {
+        Keycode::PageUp => offset - page,
+        Keycode::PageDown => offset + page,
+        Keycode::Home => 0.0,
+        Keycode::End => scalar::INFINITY,
+        _ => return None,
+    })
+}
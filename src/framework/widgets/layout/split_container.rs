@@ -0,0 +1,400 @@
+use std::time::Duration;
+
+use super::container::{ChildState, ContainerSize};
+use crate::prelude::*;
+use skia::Point;
+
+/// One of the two sides of an [HSplit] or [VSplit].
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum SplitSide {
+    First,
+    Second,
+}
+
+impl Default for SplitSide {
+    fn default() -> Self {
+        Self::First
+    }
+}
+
+/// Two presses of the divider this close together make a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Both split containers are the same but for the axis they split along, so
+/// they're made from one definition here. `$main` picks the component of
+/// vectors and `$size_main` that of sizes along the split axis.
+macro_rules! split_container {
+    (
+        $(#[$doc:meta])*
+        $name:ident,
+        $main:ident,
+        $cross:ident,
+        $size_main:ident,
+        $size_cross:ident,
+        $decrease:ident,
+        $increase:ident
+    ) => {
+        $(#[$doc])*
+        pub struct $name<A: Widget + ?Sized, B: Widget + ?Sized> {
+            first: (Wrap<A>, ChildState),
+            second: (Wrap<B>, ChildState),
+            size: ContainerSize,
+            divider_paint: Paint,
+            ratio: scalar,
+            min_first: scalar,
+            min_second: scalar,
+            collapse_side: SplitSide,
+            collapsed: bool,
+            /// Where the pointer grabbed the divider, from its start, while
+            /// it's being dragged.
+            drag: Option<scalar>,
+            last_press: Option<Duration>,
+            on_change_fns: Vec<Box<dyn FnMut(scalar)>>,
+            /// The size of the first side, as last laid out.
+            split: scalar,
+            current_size: Size,
+        }
+
+        impl<A: Widget + ?Sized, B: Widget + ?Sized> $name<A, B> {
+            const DIVIDER: scalar = 6.0;
+            const KEY_STEP: scalar = 16.0;
+
+            pub fn new(
+                first: Wrap<A>,
+                second: Wrap<B>,
+                size: ContainerSize,
+                divider_paint: Paint,
+            ) -> Wrap<Self> {
+                FrameworkState::request_load();
+                Self {
+                    first: (first, ChildState::new()),
+                    second: (second, ChildState::new()),
+                    size,
+                    divider_paint,
+                    ratio: 0.5,
+                    min_first: 0.0,
+                    min_second: 0.0,
+                    collapse_side: SplitSide::First,
+                    collapsed: false,
+                    drag: None,
+                    last_press: None,
+                    on_change_fns: vec![],
+                    split: 0.0,
+                    current_size: Size::default(),
+                }
+                .into()
+            }
+
+            pub fn first(&self) -> &Wrap<A> {
+                &self.first.0
+            }
+
+            pub fn second(&self) -> &Wrap<B> {
+                &self.second.0
+            }
+
+            /// How much of the space besides the divider goes to the first
+            /// side, from 0 to 1. Keep this to restore the split later on
+            /// with [Self::set_ratio].
+            pub fn ratio(&self) -> scalar {
+                self.ratio
+            }
+
+            pub fn set_ratio(&mut self, ratio: scalar) -> &mut Self {
+                self.ratio = ratio.clamp(0.0, 1.0);
+                self.collapsed = false;
+                self.relayout();
+                self
+            }
+
+            /// Sets the smallest each side can be made by dragging, on top
+            /// of the minimum sizes of the children themselves.
+            pub fn set_min_sizes(
+                &mut self,
+                first: scalar,
+                second: scalar,
+            ) -> &mut Self {
+                self.min_first = first.max(0.0);
+                self.min_second = second.max(0.0);
+                self.relayout();
+                self
+            }
+
+            /// Sets which side double clicking the divider collapses.
+            pub fn set_collapse_side(&mut self, side: SplitSide) -> &mut Self {
+                self.collapse_side = side;
+                self
+            }
+
+            pub fn is_collapsed(&self) -> bool {
+                self.collapsed
+            }
+
+            /// Collapses the collapsing side down to nothing, or brings it
+            /// back to where it was. The ratio is kept either way.
+            pub fn set_collapsed(&mut self, collapsed: bool) -> &mut Self {
+                self.collapsed = collapsed;
+                self.relayout();
+                self
+            }
+
+            /// Adds a callback, called with the new ratio whenever the user
+            /// moves the divider.
+            pub fn on_change<F: FnMut(scalar) + 'static>(&mut self, f: F) {
+                self.on_change_fns.push(Box::new(f));
+            }
+
+            /// The smallest and largest the first side can be, given the
+            /// space besides the divider.
+            fn limits(&self, space: scalar) -> (scalar, scalar) {
+                let min_first = self
+                    .min_first
+                    .max(self.first.1.layout_size.$size_main.min);
+                let min_second = self
+                    .min_second
+                    .max(self.second.1.layout_size.$size_main.min);
+                let max = (space - min_second).max(0.0);
+                (min_first.min(max), max)
+            }
+
+            fn space(&self) -> scalar {
+                (self.current_size.$size_main - Self::DIVIDER).max(0.0)
+            }
+
+            fn divider_rect(&self) -> Rect {
+                let mut start = Point::default();
+                start.$main = self.split;
+                let mut size = Point::default();
+                size.$main = Self::DIVIDER;
+                size.$cross = self.current_size.$size_cross;
+                Rect::from_point_and_size(start, (size.x, size.y))
+            }
+
+            /// Moves the divider so the first side is `split` long, as
+            /// asked for by the user.
+            fn move_divider(&mut self, split: scalar) {
+                let space = self.space();
+                if space <= 0.0 {
+                    return;
+                }
+                let (min, max) = self.limits(space);
+                self.ratio = split.max(min).min(max) / space;
+                self.collapsed = false;
+                self.relayout();
+                for f in &mut self.on_change_fns {
+                    f(self.ratio);
+                }
+            }
+
+            fn press_divider(&mut self, state: &mut WidgetState, p: Point) {
+                let now = State::elapsed_draw();
+                let double = self
+                    .last_press
+                    .map_or(false, |t| now - t <= DOUBLE_CLICK);
+                if double {
+                    self.last_press = None;
+                    self.collapsed = !self.collapsed;
+                    self.relayout();
+                    for f in &mut self.on_change_fns {
+                        f(self.ratio);
+                    }
+                } else {
+                    self.last_press = Some(now);
+                    self.drag = Some(p.$main - self.split);
+                }
+                if !state.is_focused() {
+                    state.grab_focus();
+                }
+            }
+
+            fn relayout(&mut self) {
+                let size = self.current_size;
+                let space = self.space();
+                let (min, max) = self.limits(space);
+                self.split = match (self.collapsed, self.collapse_side) {
+                    (true, SplitSide::First) => 0.0,
+                    (true, SplitSide::Second) => space,
+                    _ => (space * self.ratio).max(min).min(max),
+                };
+
+                let mut first = size;
+                first.$size_main = self.split;
+                self.first.1.position = Point::default();
+                self.first.1.maybe_set_size(&mut self.first.0, first);
+
+                let mut second = size;
+                second.$size_main = space - self.split;
+                let mut position = Point::default();
+                position.$main = self.split + Self::DIVIDER;
+                self.second.1.position = position;
+                self.second.1.maybe_set_size(&mut self.second.0, second);
+            }
+        }
+
+        impl<A: Widget + ?Sized, B: Widget + ?Sized> Widget for $name<A, B> {
+            fn load(
+                &mut self,
+                _state: &mut WidgetState,
+                stack: &mut ResourceStack,
+            ) {
+                self.first.0.load(stack);
+                self.second.0.load(stack);
+            }
+
+            fn update(&mut self, _state: &mut WidgetState) {
+                self.first.0.update();
+                self.second.0.update();
+            }
+
+            fn input(
+                &mut self,
+                state: &mut WidgetState,
+                event: &InputEvent,
+            ) -> bool {
+                match event {
+                    InputEvent::MouseDown(MouseButton::Left, p)
+                        if self.divider_rect().contains(*p) =>
+                    {
+                        self.press_divider(state, *p);
+                        return true;
+                    }
+                    // Focus stays with the divider after it's pressed, so
+                    // it takes arrow keys, until something else is pressed.
+                    InputEvent::MouseDown(..) if state.is_focused() => {
+                        self.drag = None;
+                        state.release_focus();
+                        FrameworkState::resend_unfocused_input();
+                        return false;
+                    }
+                    InputEvent::MouseMove(p) => {
+                        if let Some(grab) = self.drag {
+                            self.move_divider(p.$main - grab);
+                            return true;
+                        }
+                    }
+                    InputEvent::MouseUp(MouseButton::Left, _)
+                        if self.drag.is_some() =>
+                    {
+                        self.drag = None;
+                        return true;
+                    }
+                    InputEvent::KeyDown(Keycode::Escape)
+                        if state.is_focused() =>
+                    {
+                        self.drag = None;
+                        state.release_focus();
+                        return true;
+                    }
+                    InputEvent::KeyDown(key) if state.is_focused() => {
+                        let step = match key {
+                            Keycode::$decrease => Some(-Self::KEY_STEP),
+                            Keycode::$increase => Some(Self::KEY_STEP),
+                            _ => None,
+                        };
+                        if let Some(step) = step {
+                            self.move_divider(self.split + step);
+                            return true;
+                        }
+                    }
+                    _ => {}
+                }
+
+                let c = event.is_consumable();
+                let m = Matrix::translate(self.second.1.position);
+                if let Some(e) = event.reverse_map_position(m) {
+                    if self.second.0.input(&e) && c {
+                        return true;
+                    }
+                }
+                let m = Matrix::translate(self.first.1.position);
+                event
+                    .reverse_map_position(m)
+                    .map_or(false, |e| self.first.0.input(&e))
+            }
+
+            fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+                let (a, sa, ca) = self.first.1.size(&mut self.first.0);
+                let (b, sb, cb) = self.second.1.size(&mut self.second.0);
+                let mut min = Point::default();
+                min.$main =
+                    a.$size_main.min + b.$size_main.min + Self::DIVIDER;
+                min.$cross = a.$size_cross.min.max(b.$size_cross.min);
+                (
+                    self.size.apply(&LayoutSize::min(min.x, min.y)),
+                    sa || ca || sb || cb,
+                )
+            }
+
+            fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+                self.current_size = size;
+                self.relayout();
+            }
+
+            fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+                let m = Matrix::translate(self.first.1.position);
+                let mut first = self.current_size;
+                first.$size_main = self.split;
+                canvas.save();
+                canvas.concat(&m);
+                canvas.clip_rect(Rect::from_size(first), None, true);
+                self.first.0.draw(canvas);
+                canvas.restore();
+
+                let m = Matrix::translate(self.second.1.position);
+                let mut second = self.current_size;
+                second.$size_main = self.space() - self.split;
+                canvas.save();
+                canvas.concat(&m);
+                canvas.clip_rect(Rect::from_size(second), None, true);
+                self.second.0.draw(canvas);
+                canvas.restore();
+
+                canvas.draw_rect(self.divider_rect(), &self.divider_paint);
+            }
+
+            fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+                f(&self.first.0);
+                f(&self.second.0);
+            }
+
+            fn properties(&self) -> Vec<(&'static str, String)> {
+                vec![
+                    ("ratio", self.ratio.to_string()),
+                    ("collapsed", self.collapsed.to_string()),
+                ]
+            }
+        }
+    };
+}
+
+split_container!(
+    /// Two children side by side, with a divider between them that can be
+    /// dragged to share out the width.
+    ///
+    /// Clicking the divider focuses it, letting the left and right arrow
+    /// keys move it until something else is clicked or Escape is pressed.
+    /// Double clicking it collapses one side.
+    HSplit,
+    x,
+    y,
+    width,
+    height,
+    Left,
+    Right
+);
+
+split_container!(
+    /// Two children one above the other, with a divider between them that
+    /// can be dragged to share out the height.
+    ///
+    /// Clicking the divider focuses it, letting the up and down arrow keys
+    /// move it until something else is clicked or Escape is pressed. Double
+    /// clicking it collapses one side.
+    VSplit,
+    y,
+    x,
+    height,
+    width,
+    Up,
+    Down
+);
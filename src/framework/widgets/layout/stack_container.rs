@@ -0,0 +1,318 @@
+use crate::prelude::*;
+
+use super::container::{Axes, ChildState, ContainerSize};
+
+/// A point on a rectangle, as a fraction of its size.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnchorPoint {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// Any other point, with (0, 0) at the top left and (1, 1) at the
+    /// bottom right.
+    Custom(Vector),
+}
+
+impl AnchorPoint {
+    pub fn fraction(self) -> Vector {
+        let (x, y) = match self {
+            AnchorPoint::TopLeft => (0.0, 0.0),
+            AnchorPoint::TopCenter => (0.5, 0.0),
+            AnchorPoint::TopRight => (1.0, 0.0),
+            AnchorPoint::CenterLeft => (0.0, 0.5),
+            AnchorPoint::Center => (0.5, 0.5),
+            AnchorPoint::CenterRight => (1.0, 0.5),
+            AnchorPoint::BottomLeft => (0.0, 1.0),
+            AnchorPoint::BottomCenter => (0.5, 1.0),
+            AnchorPoint::BottomRight => (1.0, 1.0),
+            AnchorPoint::Custom(v) => return v,
+        };
+        Vector::new(x, y)
+    }
+
+    /// The point on a rectangle of the given size.
+    pub fn on(self, size: Size) -> Vector {
+        let f = self.fraction();
+        Vector::new(f.x * size.width, f.y * size.height)
+    }
+}
+
+impl Default for AnchorPoint {
+    fn default() -> Self {
+        Self::TopLeft
+    }
+}
+
+/// Where a child goes in a [StackContainer].
+///
+/// The child's `origin` is put on the container's `anchor`, then moved by
+/// `offset`. Along `relative_axes`, the child's size is `relative_size`
+/// times the container's; along other axes, it's the child's own size, or
+/// all of the container if the child expands.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StackPosition {
+    pub anchor: AnchorPoint,
+    pub origin: AnchorPoint,
+    pub offset: Vector,
+    pub relative_axes: Axes,
+    pub relative_size: Vector,
+    /// Children with a higher z-index are drawn above, and get input
+    /// before, those with a lower one. Ties go by the order added.
+    pub z_index: i32,
+}
+
+impl StackPosition {
+    pub fn new(anchor: AnchorPoint, origin: AnchorPoint) -> Self {
+        Self {
+            anchor,
+            origin,
+            offset: Vector::default(),
+            relative_axes: Axes::None,
+            relative_size: Vector::new(1.0, 1.0),
+            z_index: 0,
+        }
+    }
+
+    /// Anchors the child by the same point on both, such as the bottom
+    /// right corner of the child in the bottom right corner of the
+    /// container.
+    pub fn at(point: AnchorPoint) -> Self {
+        Self::new(point, point)
+    }
+
+    pub fn offset(mut self, offset: Vector) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn relative_size(mut self, axes: Axes, size: Vector) -> Self {
+        self.relative_axes = axes;
+        self.relative_size = size;
+        self
+    }
+
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    fn child_size(&self, layout_size: LayoutSize, parent: Size) -> Size {
+        let axes = self.relative_axes;
+        let size = self.relative_size;
+        layout_size.fit_aspect(Size::new(
+            if axes.has_x() {
+                parent.width * size.x
+            } else {
+                layout_size.width.layout_one(parent.width)
+            },
+            if axes.has_y() {
+                parent.height * size.y
+            } else {
+                layout_size.height.layout_one(parent.height)
+            },
+        ))
+    }
+}
+
+impl Default for StackPosition {
+    fn default() -> Self {
+        Self::new(AnchorPoint::TopLeft, AnchorPoint::TopLeft)
+    }
+}
+
+/// Layers children on top of one another, each placed freely with a
+/// [StackPosition], for things like HUDs.
+///
+/// ```ignore
+/// let mut hud = StackContainer::new(
+///     ContainerSize::ZERO.expand_width().expand_height(),
+/// );
+/// hud.inner_mut()
+///     .add_child(game_view, StackPosition::default()
+///         .relative_size(Axes::Both, Vector::new(1.0, 1.0)))
+///     .add_child(score, StackPosition::at(AnchorPoint::TopRight)
+///         .offset(Vector::new(-16.0, 16.0))
+///         .z_index(1));
+/// ```
+pub struct StackContainer<T: Widget + ?Sized> {
+    children: Vec<(Wrap<T>, StackPosition, ChildState)>,
+    /// Indices into `children`, in drawing order.
+    order: Vec<usize>,
+    size: ContainerSize,
+    needs_layout: bool,
+}
+
+pub type StackContainerDyn = StackContainer<dyn Widget>;
+
+impl<T: Widget + ?Sized> StackContainer<T> {
+    pub fn new(size: ContainerSize) -> Wrap<Self> {
+        // `FrameworkState::request_load();` here is not needed, as there are
+        // no children just yet.
+        Self {
+            children: vec![],
+            order: vec![],
+            size,
+            needs_layout: false,
+        }
+        .into()
+    }
+
+    pub fn add_child(
+        &mut self,
+        child: Wrap<T>,
+        position: StackPosition,
+    ) -> &mut Self {
+        FrameworkState::request_load();
+        self.children.push((child, position, ChildState::new()));
+        self.reorder();
+        self
+    }
+
+    /// Removes and returns the child at `index`, in the order added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_child(&mut self, index: usize) -> Wrap<T> {
+        let child = self.children.remove(index).0;
+        self.reorder();
+        child
+    }
+
+    pub fn clear_children(&mut self) {
+        self.children.clear();
+        self.reorder();
+    }
+
+    pub fn position(&self, index: usize) -> Option<StackPosition> {
+        self.children.get(index).map(|(_, position, _)| *position)
+    }
+
+    /// Moves the child at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_position(&mut self, index: usize, position: StackPosition) {
+        self.children[index].1 = position;
+        self.reorder();
+    }
+
+    /// Changes the z-index of the child at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_z_index(&mut self, index: usize, z_index: i32) {
+        self.children[index].1.z_index = z_index;
+        self.reorder();
+    }
+
+    pub fn children(&self) -> impl ExactSizeIterator<Item = &Wrap<T>> {
+        self.children.iter().map(|(child, _, _)| child)
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn reorder(&mut self) {
+        let children = &self.children;
+        self.order = (0..children.len()).collect();
+        // Stable, so ties stay in the order added.
+        self.order.sort_by_key(|&i| children[i].1.z_index);
+        self.needs_layout = true;
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for StackContainer<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        for (child, _, _) in &mut self.children {
+            child.load(stack);
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        for (child, _, _) in &mut self.children {
+            child.update();
+        }
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        let c = event.is_consumable();
+        let mut any = false;
+        for &i in self.order.iter().rev() {
+            let (child, _, state) = &mut self.children[i];
+            if let Some(event) =
+                event.reverse_map_position(Matrix::translate(state.position))
+            {
+                if child.input(&event) {
+                    any = true;
+                    if c {
+                        break;
+                    }
+                }
+            }
+        }
+        any
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut children_changed = std::mem::take(&mut self.needs_layout);
+        let mut width_min = 0.0f32;
+        let mut height_min = 0.0f32;
+        for (child, position, state) in &mut self.children {
+            let (size, s, c) = state.size(child);
+            children_changed |= s || c;
+            // Relatively sized children take whatever they're given, so
+            // they don't need any room.
+            let axes = position.relative_axes;
+            if !axes.has_x() {
+                width_min = width_min.max(size.width.min);
+            }
+            if !axes.has_y() {
+                height_min = height_min.max(size.height.min);
+            }
+        }
+        (
+            self.size.apply(&LayoutSize::min(width_min, height_min)),
+            children_changed,
+        )
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        for (child, position, state) in &mut self.children {
+            let child_size = position.child_size(state.layout_size, size);
+            state.position = position.anchor.on(size) + position.offset
+                - position.origin.on(child_size);
+            state.maybe_set_size(child, child_size);
+        }
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        for &i in &self.order {
+            let (child, _, state) = &mut self.children[i];
+            let m = Matrix::translate(state.position);
+            canvas.save();
+            canvas.concat(&m);
+            child.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for &i in &self.order {
+            f(&self.children[i].0);
+        }
+    }
+}
@@ -1,12 +1,19 @@
+use crate::game::ID;
 use crate::prelude::*;
 
-use super::container::{ChildState, ContainerSize};
+use super::align::{Align, Justify};
+use super::container::{
+    child_list_methods, distribute, ChildState, ContainerSize,
+};
 
 pub struct VContainer<T: Widget + ?Sized> {
     children: Vec<(Wrap<T>, ChildState)>,
     size: ContainerSize,
     spacing: scalar,
+    align: Align,
+    justify: Justify,
     sizes_changed: bool,
+    needs_layout: bool,
 }
 
 pub type VContainerDyn = VContainer<dyn Widget>;
@@ -19,45 +26,37 @@ impl<T: Widget + ?Sized> VContainer<T> {
             children: vec![],
             size,
             spacing: spacing.unwrap_or(0.0),
+            align: Align::default(),
+            justify: Justify::default(),
             sizes_changed: false,
+            needs_layout: false,
         }
         .into()
     }
 
-    pub fn add_child(&mut self, child: Wrap<T>) -> &mut Self {
-        FrameworkState::request_load();
-        self.children.push((child, ChildState::new()));
-        self
-    }
+    child_list_methods!();
 
     fn layout(&mut self, _state: &mut WidgetState, size: Size) {
-        let total_space = size.height;
-
-        let mut min = self.preoccupied();
-        let mut expand = 0.0f32;
-
-        for (_, state) in &self.children {
-            min += state.layout_size.height.min;
-            if let Some(e) = state.layout_size.height.expand {
-                expand += e;
-            }
-        }
-
-        let space_left = (total_space - min).max(0.0);
-        let mut offset = 0.0;
-        for (child, state) in &mut self.children {
-            let mut height = state.layout_size.height.min;
-            if let Some(e) = state.layout_size.height.expand {
-                height += space_left * e / expand;
-            }
-            state.position.set(0.0, offset);
-            offset += height + self.spacing;
-            let width = if state.layout_size.width.expand.is_some() {
-                size.width
-            } else {
-                state.layout_size.width.min.min(size.width)
-            };
-            state.maybe_set_size(child, Size::new(width, height));
+        let space = (size.height - self.preoccupied()).max(0.0);
+        let dims: Vec<_> = self
+            .children
+            .iter()
+            .map(|(_, state)| state.layout_size.height)
+            .collect();
+        let sizes = distribute(&dims, space);
+
+        let space_left = (space - sizes.iter().sum::<scalar>()).max(0.0);
+        let (mut offset, gap) =
+            self.justify.distribute(space_left, self.children.len());
+        for ((child, state), height) in self.children.iter_mut().zip(sizes) {
+            let align = state.align.unwrap_or(self.align);
+            let width = align.cross_size(state.layout_size.width, size.width);
+            let child_size =
+                state.layout_size.fit_aspect(Size::new(width, height));
+            let x = align.cross_offset(child_size.width, size.width);
+            state.position.set(x, offset);
+            offset += height + self.spacing + gap;
+            state.maybe_set_size(child, child_size);
         }
     }
 
@@ -102,7 +101,9 @@ impl<T: Widget + ?Sized> Widget for VContainer<T> {
         let mut width_min = 0.0f32;
 
         self.sizes_changed = false;
-        let mut children_changed = false;
+        // Changes to the children or how they're arranged need laying out
+        // again, even if the total size stays the same.
+        let mut children_changed = std::mem::take(&mut self.needs_layout);
 
         for (child, state) in &mut self.children {
             let (size, s, c) = state.size(child);
@@ -122,6 +123,12 @@ impl<T: Widget + ?Sized> Widget for VContainer<T> {
         self.layout(state, size);
     }
 
+    fn baseline(&self, _state: &WidgetState) -> Option<scalar> {
+        self.children.iter().find_map(|(child, state)| {
+            child.baseline().map(|b| b + state.position.y)
+        })
+    }
+
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
         for (child, state) in &mut self.children {
             let m = Matrix::translate(state.position);
@@ -131,4 +138,10 @@ impl<T: Widget + ?Sized> Widget for VContainer<T> {
             canvas.restore();
         }
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for (child, _) in &self.children {
+            f(child);
+        }
+    }
 }
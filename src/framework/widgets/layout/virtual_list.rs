@@ -0,0 +1,476 @@
+use std::ops::Range;
+
+use super::super::Spring;
+use super::container::ChildState;
+use super::scroll_offset::{page_key, reveal, ScrollOffset, LINE};
+use crate::game::ID;
+use crate::prelude::*;
+
+type ItemEstimator = dyn FnMut(usize) -> scalar;
+type ItemBuilder<T> = dyn FnMut(usize, Option<Wrap<T>>) -> Wrap<T>;
+
+/// Item heights, kept as a Fenwick tree so that the offset of any item, and
+/// the item at any offset, can be found in logarithmic time.
+struct Heights {
+    values: Vec<scalar>,
+    tree: Vec<scalar>,
+}
+
+impl Heights {
+    fn new(values: Vec<scalar>) -> Self {
+        let mut tree = vec![0.0];
+        tree.extend_from_slice(&values);
+        for i in 1..tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+        Self { values, tree }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get(&self, index: usize) -> scalar {
+        self.values[index]
+    }
+
+    fn set(&mut self, index: usize, value: scalar) {
+        let delta = value - self.values[index];
+        self.values[index] = value;
+        let mut j = index + 1;
+        while j < self.tree.len() {
+            self.tree[j] += delta;
+            j += j & j.wrapping_neg();
+        }
+    }
+
+    /// The total height of the items before `index`.
+    fn offset_of(&self, index: usize) -> scalar {
+        let mut sum = 0.0;
+        let mut j = index;
+        while j > 0 {
+            sum += self.tree[j];
+            j -= j & j.wrapping_neg();
+        }
+        sum
+    }
+
+    fn total(&self) -> scalar {
+        self.offset_of(self.len())
+    }
+
+    /// The index of the item covering `offset`, or the last item if it's
+    /// past the end.
+    fn find(&self, offset: scalar) -> usize {
+        let n = self.len();
+        let mut index = 0;
+        let mut left = offset;
+        let mut step = n.next_power_of_two();
+        while step > 0 {
+            let next = index + step;
+            if next <= n && self.tree[next] <= left {
+                index = next;
+                left -= self.tree[next];
+            }
+            step >>= 1;
+        }
+        index.min(n.saturating_sub(1))
+    }
+}
+
+struct Item<T: Widget + ?Sized> {
+    index: usize,
+    widget: Wrap<T>,
+    state: ChildState,
+}
+
+/// A scrolling list of any number of items, of which only those on screen
+/// exist as widgets.
+///
+/// Items are made by a builder closure, given the index of the item and,
+/// when there is one, a widget from an item that went off screen, so it can
+/// be reused rather than built again. Until an item has been on screen, its
+/// height comes from the estimator closure; once it has, its own minimum
+/// height is used, and the scroll offset moves along with any change above
+/// the screen so the content doesn't jump.
+///
+/// ```ignore
+/// let list = VirtualList::new(
+///     LayoutSize::ZERO.expand_width().expand_height(),
+///     songs.len(),
+///     |_| 32.0,
+///     move |i, recycled| match recycled {
+///         Some(mut text) => {
+///             text.inner_mut().set_text(songs[i].title.clone());
+///             text
+///         }
+///         None => Text::new(/* ... */),
+///     },
+/// );
+/// ```
+pub struct VirtualList<T: Widget + ?Sized> {
+    estimate: Box<ItemEstimator>,
+    build: Box<ItemBuilder<T>>,
+    heights: Heights,
+    /// Items on screen, in order.
+    items: Vec<Item<T>>,
+    /// Widgets of items gone off screen, for reuse.
+    pool: Vec<Wrap<T>>,
+    /// Not the ID of any widget, for taking hover away from everything in a
+    /// widget being recycled.
+    id: ID,
+    resources: Option<ResourceStack>,
+    offset: ScrollOffset<scalar>,
+    hovered: bool,
+    size: Size,
+    layout_size: LayoutSize,
+}
+
+impl<T: Widget + ?Sized> VirtualList<T> {
+    /// How far past the edges of the screen items are kept around, so
+    /// they're ready before they scroll into view.
+    const OVERSCAN: scalar = 100.0;
+    /// How many widgets of items gone off screen are kept for reuse.
+    const POOL_SIZE: usize = 32;
+
+    pub fn new<E, B>(
+        size: LayoutSize,
+        count: usize,
+        mut estimate: E,
+        build: B,
+    ) -> Wrap<Self>
+    where
+        E: FnMut(usize) -> scalar + 'static,
+        B: FnMut(usize, Option<Wrap<T>>) -> Wrap<T> + 'static,
+    {
+        // `FrameworkState::request_load();` here is not needed, as there are
+        // no items just yet.
+        let heights = Heights::new((0..count).map(&mut estimate).collect());
+        Self {
+            estimate: Box::new(estimate),
+            build: Box::new(build),
+            heights,
+            items: vec![],
+            pool: vec![],
+            id: ID::next(),
+            resources: None,
+            offset: ScrollOffset::new(),
+            hovered: false,
+            size: Size::default(),
+            layout_size: size,
+        }
+        .into()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heights.len() == 0
+    }
+
+    /// Changes the number of items. Items that are kept keep their heights,
+    /// and any new ones are estimated.
+    pub fn set_count(&mut self, count: usize) {
+        let mut values = self.heights.values.clone();
+        values.truncate(count);
+        for i in values.len()..count {
+            values.push((self.estimate)(i));
+        }
+        self.heights = Heights::new(values);
+        let (pool, id) = (&mut self.pool, self.id);
+        self.items.retain(|item| {
+            let keep = item.index < count;
+            if !keep {
+                Self::recycle(pool, id, item.widget.clone());
+            }
+            keep
+        });
+        self.rescroll();
+    }
+
+    /// Builds every item on screen again, for when the data behind them has
+    /// changed.
+    pub fn refresh(&mut self) {
+        for item in self.items.drain(..) {
+            Self::recycle(&mut self.pool, self.id, item.widget);
+        }
+    }
+
+    /// Builds the item at `index` again, if it's on screen.
+    pub fn refresh_item(&mut self, index: usize) {
+        if let Some(i) = self.items.iter().position(|i| i.index == index) {
+            let item = self.items.remove(i);
+            Self::recycle(&mut self.pool, self.id, item.widget);
+        }
+    }
+
+    /// The items on screen, including those just past the edges.
+    pub fn visible_range(&self) -> Range<usize> {
+        match (self.items.first(), self.items.last()) {
+            (Some(first), Some(last)) => first.index..last.index + 1,
+            _ => 0..0,
+        }
+    }
+
+    /// The height of all the items, going by estimates for those that
+    /// haven't been on screen.
+    pub fn content_height(&self) -> scalar {
+        self.heights.total()
+    }
+
+    /// How far the list is scrolled, or is being scrolled to.
+    pub fn scroll_offset(&self) -> scalar {
+        self.offset.target()
+    }
+
+    /// The spring smoothing out scrolling, for tuning how it feels.
+    pub fn spring_mut(&mut self) -> &mut Spring<scalar> {
+        self.offset.spring_mut()
+    }
+
+    /// Scrolls smoothly to `offset`, kept within bounds.
+    pub fn scroll_to(&mut self, offset: scalar) {
+        self.offset.scroll_to(offset, self.max_offset());
+    }
+
+    /// Scrolls straight to `offset`, kept within bounds.
+    pub fn jump_to(&mut self, offset: scalar) {
+        self.offset.jump_to(offset, self.max_offset());
+    }
+
+    /// Scrolls just far enough to show the item at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn scroll_to_item(&mut self, index: usize) {
+        let top = self.heights.offset_of(index);
+        let bottom = top + self.heights.get(index);
+        let offset = self.offset.target();
+        self.scroll_to(reveal(offset, self.size.height, top, bottom));
+    }
+
+    fn max_offset(&self) -> scalar {
+        (self.heights.total() - self.size.height).max(0.0)
+    }
+
+    fn rescroll(&mut self) {
+        self.offset.rescroll(self.max_offset());
+    }
+
+    fn scroll_key(&mut self, key: Keycode) -> bool {
+        match page_key(key, self.offset.target(), self.size.height) {
+            Some(offset) => {
+                self.scroll_to(offset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Puts the widget of an item gone off screen in `pool`, taking hover
+    /// and focus away from anything in it first, as it may come back showing
+    /// another item. `id` is the list's own.
+    fn recycle(pool: &mut Vec<Wrap<T>>, id: ID, mut widget: Wrap<T>) {
+        widget.input(&InputEvent::RemoveHoverExcept(id));
+        FrameworkState::release_focus_within(&widget);
+        pool.push(widget);
+    }
+
+    fn build_item(&mut self, index: usize) -> Item<T> {
+        let recycled = self.pool.pop();
+        let mut widget = (self.build)(index, recycled);
+        if let Some(stack) = &mut self.resources {
+            widget.load(stack);
+        }
+        Item {
+            index,
+            widget,
+            state: ChildState::new(),
+        }
+    }
+
+    /// Makes sure there's a widget for every item on screen at the current
+    /// offset, and updates the heights of those items, returning whether
+    /// any changed.
+    fn sync(&mut self) -> bool {
+        if self.heights.len() == 0 {
+            for item in self.items.drain(..) {
+                Self::recycle(&mut self.pool, self.id, item.widget);
+            }
+            return false;
+        }
+        let offset = self.offset.get();
+        let first = self.heights.find((offset - Self::OVERSCAN).max(0.0));
+        let last = self
+            .heights
+            .find(offset + self.size.height + Self::OVERSCAN);
+        let range = first..last + 1;
+
+        let mut kept = vec![];
+        for item in self.items.drain(..) {
+            if range.contains(&item.index) {
+                kept.push(item);
+            } else {
+                Self::recycle(&mut self.pool, self.id, item.widget);
+            }
+        }
+        let mut kept = kept.into_iter().peekable();
+        let mut items = Vec::with_capacity(range.len());
+        for index in range {
+            match kept.next_if(|item| item.index == index) {
+                Some(item) => items.push(item),
+                None => items.push(self.build_item(index)),
+            }
+        }
+        self.items = items;
+        self.pool.truncate(Self::POOL_SIZE);
+
+        let mut changed = false;
+        for i in 0..self.items.len() {
+            let item = &mut self.items[i];
+            let (size, _, _) = item.state.size(&mut item.widget);
+            let index = item.index;
+            let height = size.height.min;
+            let old = self.heights.get(index);
+            // LINT SUPPRESSION: Any change at all needs to be kept track of.
+            #[allow(clippy::float_cmp)]
+            if height != old {
+                changed = true;
+                self.heights.set(index, height);
+                // Keep what's on screen in place as items above it change.
+                if self.heights.offset_of(index) < offset {
+                    self.offset.shift(height - old);
+                }
+            }
+        }
+        changed
+    }
+
+    fn layout_items(&mut self) {
+        // Heights found on the way can change what's on screen, so go again
+        // a couple of times if they do.
+        for _ in 0..3 {
+            if !self.sync() {
+                break;
+            }
+        }
+        let offset = self.offset.get();
+        let width = self.size.width;
+        for item in &mut self.items {
+            let top = self.heights.offset_of(item.index);
+            item.state.position.set(0.0, top - offset);
+            let height = self.heights.get(item.index);
+            item.state
+                .maybe_set_size(&mut item.widget, Size::new(width, height));
+        }
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for VirtualList<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        for item in &mut self.items {
+            item.widget.load(stack);
+        }
+        self.resources = Some(stack.snapshot());
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        for item in &mut self.items {
+            item.widget.update();
+        }
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        let bounds = Rect::from_size(self.size);
+        if let InputEvent::MouseMove(p) = event {
+            self.hovered = bounds.contains(*p);
+        }
+        if let Some(p) = event.position() {
+            if event.is_consumable() && !bounds.contains(p) {
+                return false;
+            }
+        }
+
+        let c = event.is_consumable();
+        let mut any = false;
+        for item in self.items.iter_mut().rev() {
+            if let Some(event) = event
+                .reverse_map_position(Matrix::translate(item.state.position))
+            {
+                if item.widget.input(&event) {
+                    any = true;
+                    if c {
+                        return true;
+                    }
+                }
+            }
+        }
+        match event {
+            InputEvent::MouseScroll(amount, _) => {
+                let delta = match amount {
+                    ScrollAmount::Lines(p) => p.y * LINE,
+                    ScrollAmount::Pixels(p) => p.y,
+                };
+                self.scroll_to(self.offset.target() - delta);
+                true
+            }
+            InputEvent::KeyDown(key) if self.hovered => self.scroll_key(*key),
+            InputEvent::RemoveHoverExcept(_) => {
+                self.hovered = false;
+                any
+            }
+            _ => any,
+        }
+    }
+
+    fn hover_lost(&mut self, _state: &mut WidgetState) {
+        self.hovered = false;
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        // Items are sized as they're laid out, since which ones exist
+        // depends on the scroll offset.
+        (self.layout_size, false)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        self.rescroll();
+    }
+
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
+        self.offset.update(state.delta());
+        self.layout_items();
+        canvas.save();
+        canvas.clip_rect(Rect::from_size(self.size), None, true);
+        for item in &mut self.items {
+            let m = Matrix::translate(item.state.position);
+            canvas.save();
+            canvas.concat(&m);
+            item.widget.draw(canvas);
+            canvas.restore();
+        }
+        canvas.restore();
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for item in &self.items {
+            f(&item.widget);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        let range = self.visible_range();
+        vec![
+            ("count", self.len().to_string()),
+            ("visible", format!("{}..{}", range.start, range.end)),
+            ("offset", self.offset.get().to_string()),
+        ]
+    }
+}
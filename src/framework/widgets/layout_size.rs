@@ -1,21 +1,34 @@
 use crate::skia::{scalar, Matrix, Rect, Size, Vector};
 
+/// How much space a widget asks for.
+///
+/// More fields may be added over time, so build these from
+/// [LayoutSize::ZERO] or [LayoutSize::min] and the builder methods rather
+/// than with struct literals.
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[non_exhaustive]
 pub struct LayoutSize {
     pub width: LayoutDimension,
     pub height: LayoutDimension,
+
+    /// The ratio of width to height this widget should keep, if any.
+    /// Containers give such widgets the largest size with this ratio that
+    /// fits in the space they would otherwise get.
+    pub aspect_ratio: Option<scalar>,
 }
 
 impl LayoutSize {
     pub const ZERO: Self = Self {
         width: LayoutDimension::ZERO,
         height: LayoutDimension::ZERO,
+        aspect_ratio: None,
     };
 
     pub fn min(width: scalar, height: scalar) -> Self {
         Self {
             width: LayoutDimension::min(width),
             height: LayoutDimension::min(height),
+            aspect_ratio: None,
         }
     }
 
@@ -64,15 +77,55 @@ impl LayoutSize {
         self
     }
 
+    pub fn max_width(mut self, max: scalar) -> Self {
+        self.width.max = Some(max);
+        self
+    }
+
+    pub fn max_height(mut self, max: scalar) -> Self {
+        self.height.max = Some(max);
+        self
+    }
+
+    pub fn relative_width(mut self, fraction: scalar) -> Self {
+        self.width.relative = Some(fraction);
+        self
+    }
+
+    pub fn relative_height(mut self, fraction: scalar) -> Self {
+        self.height.relative = Some(fraction);
+        self
+    }
+
+    pub fn with_aspect_ratio(mut self, ratio: Option<scalar>) -> Self {
+        self.aspect_ratio = ratio;
+        self
+    }
+
+    /// Shrinks `size` along one axis to keep the aspect ratio, if there is
+    /// one.
+    pub fn fit_aspect(&self, size: Size) -> Size {
+        match self.aspect_ratio {
+            Some(r) if r > 0.0 && size.height > 0.0 => {
+                if size.width / size.height > r {
+                    Size::new(size.height * r, size.height)
+                } else {
+                    Size::new(size.width, size.width / r)
+                }
+            }
+            _ => size,
+        }
+    }
+
     pub fn get_min(&self) -> Size {
         Size::new(self.width.min, self.height.min)
     }
 
     pub fn layout_one(&self, size: Size) -> Size {
-        Size::new(
+        self.fit_aspect(Size::new(
             self.width.layout_one(size.width),
             self.height.layout_one(size.height),
-        )
+        ))
     }
 
     pub fn map(&self, matrix: Matrix) -> Self {
@@ -85,15 +138,32 @@ impl LayoutSize {
             .height
             .expand
             .map(|y| matrix.map_vector(Vector::new(0.0, y)).y.abs());
+        let max_width = self
+            .width
+            .max
+            .map(|x| matrix.map_vector(Vector::new(x, 0.0)).x.abs());
+        let max_height = self
+            .height
+            .max
+            .map(|y| matrix.map_vector(Vector::new(0.0, y)).y.abs());
+        let aspect_ratio = self.aspect_ratio.map(|r| {
+            let s = Self::map_size(r, 1.0, matrix);
+            s.width / s.height
+        });
         Self {
             width: LayoutDimension {
                 min: min.width.abs(),
                 expand: expand_width,
+                max: max_width,
+                relative: self.width.relative,
             },
             height: LayoutDimension {
                 min: min.height.abs(),
                 expand: expand_height,
+                max: max_height,
+                relative: self.height.relative,
             },
+            aspect_ratio,
         }
     }
 
@@ -102,7 +172,12 @@ impl LayoutSize {
     }
 }
 
+/// How much space a widget asks for along one axis.
+///
+/// Like [LayoutSize], this may gain fields, so build it from
+/// [LayoutDimension::ZERO] or [LayoutDimension::min].
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[non_exhaustive]
 pub struct LayoutDimension {
     /// The minimum number of logical pixels this dimension should receive.
     /// May not always be respected by containers, for example when they run out
@@ -115,16 +190,27 @@ pub struct LayoutDimension {
     /// A None expresses this widget should take up the minimum space,
     /// expressed in the [min](Self::min) field.
     pub expand: Option<scalar>,
+
+    /// The maximum number of logical pixels this dimension should receive,
+    /// even when expanding. The minimum wins if the two disagree.
+    pub max: Option<scalar>,
+
+    /// A fraction of the space given by the parent this dimension should
+    /// take up when not expanding, for example 0.3 for 30%. It's still
+    /// kept between the minimum and maximum.
+    pub relative: Option<scalar>,
 }
 
 impl LayoutDimension {
     pub const ZERO: Self = Self {
         min: 0.0,
         expand: None,
+        max: None,
+        relative: None,
     };
 
     pub fn min(min: scalar) -> Self {
-        Self { min, expand: None }
+        Self { min, ..Self::ZERO }
     }
 
     pub fn expand_by(mut self, expand: scalar) -> Self {
@@ -147,11 +233,35 @@ impl LayoutDimension {
         self
     }
 
+    pub fn with_max(mut self, max: Option<scalar>) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_relative(mut self, fraction: Option<scalar>) -> Self {
+        self.relative = fraction;
+        self
+    }
+
+    /// Keeps `size` within the maximum and minimum.
+    pub fn clamp(&self, size: scalar) -> scalar {
+        self.max.map_or(size, |max| size.min(max)).max(self.min)
+    }
+
+    /// The size this dimension wants out of `space`, not counting any share
+    /// of leftover space from expanding.
+    pub fn preferred(&self, space: scalar) -> scalar {
+        match self.relative {
+            Some(fraction) => self.clamp(space * fraction),
+            None => self.min,
+        }
+    }
+
     pub fn layout_one(&self, space: scalar) -> scalar {
         if self.expand.is_some() {
-            space.max(self.min)
+            self.clamp(space)
         } else {
-            self.min
+            self.preferred(space)
         }
     }
 }
@@ -39,6 +39,7 @@ impl Widget for Throbber {
             LayoutSize {
                 width: self.radius,
                 height: self.radius,
+                aspect_ratio: None,
             },
             false,
         )
@@ -48,7 +49,7 @@ impl Widget for Throbber {
         self.size = size;
     }
 
-    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
         let stroke_width = self.paint.stroke_width();
         let s = self.size.width.min(self.size.height) - stroke_width;
         canvas.draw_arc(
@@ -59,6 +60,6 @@ impl Widget for Throbber {
             false,
             &self.paint,
         );
-        self.rad += State::last_update_time_draw().as_secs_f32() * 720.0;
+        self.rad += state.delta().as_secs_f32() * 720.0;
     }
 }
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use skia::Color4f;
+
+/// Values a [Spring] can animate, treated as up to four independent
+/// components.
+pub trait SpringValue: Copy {
+    /// How many of the four components are used.
+    const COMPONENTS: usize;
+
+    fn to_components(self) -> [scalar; 4];
+
+    fn from_components(c: [scalar; 4]) -> Self;
+}
+
+impl SpringValue for scalar {
+    const COMPONENTS: usize = 1;
+
+    fn to_components(self) -> [scalar; 4] {
+        [self, 0.0, 0.0, 0.0]
+    }
+
+    fn from_components(c: [scalar; 4]) -> Self {
+        c[0]
+    }
+}
+
+impl SpringValue for Vector {
+    const COMPONENTS: usize = 2;
+
+    fn to_components(self) -> [scalar; 4] {
+        [self.x, self.y, 0.0, 0.0]
+    }
+
+    fn from_components(c: [scalar; 4]) -> Self {
+        Self::new(c[0], c[1])
+    }
+}
+
+impl SpringValue for Color4f {
+    const COMPONENTS: usize = 4;
+
+    fn to_components(self) -> [scalar; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    fn from_components(c: [scalar; 4]) -> Self {
+        Self::new(c[0], c[1], c[2], c[3])
+    }
+}
+
+/// A value pulled towards a target by a damped spring of unit mass.
+///
+/// Each step is solved exactly rather than integrated, so springs behave the
+/// same however long frames take. A damping ratio of 1, as given by
+/// [Spring::critically_damped], reaches the target as fast as possible
+/// without overshooting; lower damping bounces, higher damping crawls.
+pub struct Spring<T: SpringValue> {
+    pub stiffness: scalar,
+    pub damping: scalar,
+    /// How close to the target, and how slow, the value has to be for the
+    /// spring to settle.
+    pub precision: scalar,
+    value: [scalar; 4],
+    velocity: [scalar; 4],
+    target: [scalar; 4],
+    settled: bool,
+    on_settled: Vec<Box<dyn FnMut()>>,
+}
+
+impl<T: SpringValue> Spring<T> {
+    pub const DEFAULT_PRECISION: scalar = 0.01;
+
+    pub fn new(value: T, stiffness: scalar, damping: scalar) -> Self {
+        let value = value.to_components();
+        Self {
+            stiffness: stiffness.max(0.0),
+            damping: damping.max(0.0),
+            precision: Self::DEFAULT_PRECISION,
+            value,
+            velocity: [0.0; 4],
+            target: value,
+            settled: true,
+            on_settled: vec![],
+        }
+    }
+
+    /// A spring that doesn't overshoot, with the given stiffness.
+    pub fn critically_damped(value: T, stiffness: scalar) -> Self {
+        let stiffness = stiffness.max(0.0);
+        Self::new(value, stiffness, 2.0 * stiffness.sqrt())
+    }
+
+    /// A spring with the given stiffness and damping ratio, 1 being
+    /// critically damped and anything lower bouncing.
+    pub fn with_ratio(value: T, stiffness: scalar, ratio: scalar) -> Self {
+        let stiffness = stiffness.max(0.0);
+        Self::new(value, stiffness, 2.0 * ratio * stiffness.sqrt())
+    }
+
+    /// How damped the spring is, 1 being critically damped.
+    pub fn damping_ratio(&self) -> scalar {
+        if self.stiffness > 0.0 {
+            self.damping / (2.0 * self.stiffness.sqrt())
+        } else {
+            scalar::INFINITY
+        }
+    }
+
+    /// The value as of the last call to [Spring::update].
+    pub fn get(&self) -> T {
+        T::from_components(self.value)
+    }
+
+    pub fn velocity(&self) -> T {
+        T::from_components(self.velocity)
+    }
+
+    pub fn target(&self) -> T {
+        T::from_components(self.target)
+    }
+
+    /// Pulls the spring towards `target`, keeping its current velocity.
+    pub fn set_target(&mut self, target: T) {
+        self.target = target.to_components();
+        self.settled = false;
+    }
+
+    /// Jumps to `value` and stops there.
+    pub fn set(&mut self, value: T) {
+        self.value = value.to_components();
+        self.target = self.value;
+        self.velocity = [0.0; 4];
+        self.settled = true;
+    }
+
+    /// Sets the velocity, in units per second, for example to carry on from
+    /// a drag.
+    pub fn set_velocity(&mut self, velocity: T) {
+        self.velocity = velocity.to_components();
+        self.settled = false;
+    }
+
+    /// Whether the spring has come to rest at its target.
+    pub fn is_settled(&self) -> bool {
+        self.settled
+    }
+
+    /// Adds a callback, called every time the spring comes to rest.
+    pub fn on_settled<F: FnMut() + 'static>(&mut self, f: F) {
+        self.on_settled.push(Box::new(f));
+    }
+
+    /// Advances the spring by `delta`, returning the new value.
+    pub fn update(&mut self, delta: Duration) -> T {
+        if self.settled {
+            return self.get();
+        }
+        let t = delta.as_secs_f32();
+        let (stiffness, damping) = (self.stiffness, self.damping);
+        let mut at_rest = true;
+        let components = self
+            .value
+            .iter_mut()
+            .zip(&mut self.velocity)
+            .zip(&self.target)
+            .take(T::COMPONENTS);
+        for ((value, velocity), target) in components {
+            let x = *value - target;
+            let (x, v) = solve(stiffness, damping, x, *velocity, t);
+            *value = target + x;
+            *velocity = v;
+            at_rest &= x.abs() < self.precision && v.abs() < self.precision;
+        }
+        if at_rest {
+            self.set(self.target());
+            for f in &mut self.on_settled {
+                f();
+            }
+        }
+        self.get()
+    }
+}
+
+/// Solves a single spring component over `t` seconds, given its
+/// displacement from the target and its velocity.
+fn solve(
+    stiffness: scalar,
+    damping: scalar,
+    x: scalar,
+    v: scalar,
+    t: scalar,
+) -> (scalar, scalar) {
+    let w = stiffness.sqrt();
+    if w == 0.0 {
+        // No pull at all, so only damping applies.
+        if damping == 0.0 {
+            return (x + v * t, v);
+        }
+        let decay = (-damping * t).exp();
+        return (x + v * (1.0 - decay) / damping, v * decay);
+    }
+    let zeta = damping / (2.0 * w);
+    if (zeta - 1.0).abs() < 1e-3 {
+        let decay = (-w * t).exp();
+        let b = v + w * x;
+        ((x + b * t) * decay, (v - w * b * t) * decay)
+    } else if zeta < 1.0 {
+        let a = zeta * w;
+        let wd = w * (1.0 - zeta * zeta).sqrt();
+        let decay = (-a * t).exp();
+        let (sin, cos) = (wd * t).sin_cos();
+        let x1 = decay * (x * cos + (v + a * x) / wd * sin);
+        let v1 = decay * (v * cos - (a * v + w * w * x) / wd * sin);
+        (x1, v1)
+    } else {
+        let root = (zeta * zeta - 1.0).sqrt();
+        let r1 = -w * (zeta - root);
+        let r2 = -w * (zeta + root);
+        let c2 = (v - r1 * x) / (r2 - r1);
+        let c1 = x - c2;
+        let (e1, e2) = ((r1 * t).exp(), (r2 * t).exp());
+        (c1 * e1 + c2 * e2, r1 * c1 * e1 + r2 * c2 * e2)
+    }
+}
@@ -231,6 +231,17 @@ impl Widget for Text {
             canvas.restore();
         }
     }
+
+    fn baseline(&self, _state: &WidgetState) -> Option<scalar> {
+        self.paragraph.as_ref().map(|_| self.draw_offset().y)
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("text", self.text.clone()),
+            ("layout_mode", format!("{:?}", self.layout_mode)),
+        ]
+    }
 }
 
 struct Word {
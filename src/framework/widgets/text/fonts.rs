@@ -82,6 +82,10 @@ impl<T: Widget + ?Sized> Widget for Fonts<T> {
     fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
         self.inner.draw(canvas);
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.inner);
+    }
 }
 
 pub struct FontResource {
@@ -0,0 +1,244 @@
+#[cfg(any(feature = "timeline-json", feature = "timeline-ron"))]
+mod format;
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::DrawProps;
+use crate::prelude::*;
+use skia::Color4f;
+
+#[cfg(any(feature = "timeline-json", feature = "timeline-ron"))]
+pub use format::TimelineError;
+
+/// A value a [Track] passes through at a given time.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Keyframe<T> {
+    pub time: Duration,
+    pub value: T,
+    /// The easing used from this keyframe to the next one.
+    pub easing: EasingFn,
+}
+
+/// Keyframes for a single value, kept sorted by time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track<T> {
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Lerp + Copy> Track<T> {
+    pub fn new() -> Self {
+        Self { keyframes: vec![] }
+    }
+
+    /// Adds a keyframe, see [Track::insert].
+    pub fn key(mut self, time: Duration, value: T, easing: EasingFn) -> Self {
+        self.insert(Keyframe {
+            time,
+            value,
+            easing,
+        });
+        self
+    }
+
+    /// Adds a keyframe, replacing any other one at the same time.
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        let keyframes = &mut self.keyframes;
+        match keyframes.binary_search_by_key(&keyframe.time, |k| k.time) {
+            Ok(i) => keyframes[i] = keyframe,
+            Err(i) => keyframes.insert(i, keyframe),
+        }
+    }
+
+    /// Removes and returns the keyframe at `time`, if there is one.
+    pub fn remove(&mut self, time: Duration) -> Option<Keyframe<T>> {
+        let keyframes = &mut self.keyframes;
+        match keyframes.binary_search_by_key(&time, |k| k.time) {
+            Ok(i) => Some(keyframes.remove(i)),
+            Err(_) => None,
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// The time of the last keyframe.
+    pub fn duration(&self) -> Duration {
+        self.keyframes.last().map(|k| k.time).unwrap_or_default()
+    }
+
+    /// The value at time `t`, or `None` if there are no keyframes. The
+    /// value holds still before the first keyframe and after the last one.
+    pub fn sample(&self, t: Duration) -> Option<T> {
+        let keyframes = &self.keyframes;
+        let next = keyframes.partition_point(|k| k.time <= t);
+        if next == 0 {
+            return keyframes.first().map(|k| k.value);
+        }
+        let from = &keyframes[next - 1];
+        let to = match keyframes.get(next) {
+            Some(to) => to,
+            None => return Some(from.value),
+        };
+        let length = (to.time - from.time).as_secs_f32();
+        let progress = (t - from.time).as_secs_f32() / length;
+        Some(from.value.lerp(to.value, from.easing.apply(progress)))
+    }
+}
+
+impl<T: Lerp + Copy> Default for Track<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keyframed animation of several [DrawProps] at once, for animations
+/// authored ahead of time, possibly in a file.
+///
+/// Unlike a [Sequence](super::Sequence), a timeline's value depends only on
+/// its position, so widgets playing one can be scrubbed by seeking or
+/// pausing the [Clock](super::Clock) they follow. Empty tracks leave their
+/// property alone.
+///
+/// ```ignore
+/// let ms = Duration::from_millis;
+/// let intro = Timeline {
+///     alpha: Track::new()
+///         .key(ms(0), 0.0, EasingFn::OutCubic)
+///         .key(ms(400), 1.0, EasingFn::Linear),
+///     position: Track::new()
+///         .key(ms(0), Vector::new(0.0, 40.0), EasingFn::OutQuint)
+///         .key(ms(600), Vector::default(), EasingFn::Linear),
+///     ..Timeline::default()
+/// };
+/// logo.play_timeline(Rc::new(intro));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timeline {
+    pub alpha: Track<scalar>,
+    pub position: Track<Vector>,
+    pub scale: Track<Vector>,
+    pub rotation: Track<scalar>,
+    pub colour: Track<Color4f>,
+    pub size: Track<Size>,
+    pub transform: Track<Matrix>,
+    /// Whether the timeline starts over once done.
+    pub looping: bool,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The time of the last keyframe across all tracks.
+    pub fn duration(&self) -> Duration {
+        [
+            self.alpha.duration(),
+            self.position.duration(),
+            self.scale.duration(),
+            self.rotation.duration(),
+            self.colour.duration(),
+            self.size.duration(),
+            self.transform.duration(),
+        ]
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+    }
+
+    /// Sets every animated property in `props` to its value at `t`, after
+    /// wrapping `t` around if looping.
+    pub fn apply(&self, t: Duration, props: &mut DrawProps) {
+        let t = self.local_time(t);
+        if let Some(v) = self.alpha.sample(t) {
+            props.alpha = v;
+        }
+        if let Some(v) = self.position.sample(t) {
+            props.position = v;
+        }
+        if let Some(v) = self.scale.sample(t) {
+            props.scale = v;
+        }
+        if let Some(v) = self.rotation.sample(t) {
+            props.rotation = v;
+        }
+        if let Some(v) = self.colour.sample(t) {
+            props.colour = v;
+        }
+        if let Some(v) = self.size.sample(t) {
+            props.size = Some(v);
+        }
+        if let Some(v) = self.transform.sample(t) {
+            props.transform = v;
+        }
+    }
+
+    /// Whether the timeline has nothing left to play at `t`.
+    pub fn is_done(&self, t: Duration) -> bool {
+        !self.looping && t >= self.duration()
+    }
+
+    fn local_time(&self, t: Duration) -> Duration {
+        let duration = self.duration();
+        if self.looping && duration > Duration::default() {
+            let nanos = t.as_nanos() % duration.as_nanos();
+            Duration::from_nanos(nanos as u64)
+        } else {
+            t
+        }
+    }
+}
+
+/// A timeline being played on a widget, driven by `WidgetState`.
+pub(crate) struct TimelinePlayback {
+    timeline: Rc<Timeline>,
+    start: Option<Duration>,
+    /// Whether it was done as of the last time it was applied.
+    finished: bool,
+}
+
+impl TimelinePlayback {
+    /// Plays `timeline` from `start` on the widget's clock, or from the
+    /// first time it's applied if `None`.
+    pub(crate) fn new(timeline: Rc<Timeline>, start: Option<Duration>) -> Self {
+        Self {
+            timeline,
+            start,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn timeline(&self) -> &Rc<Timeline> {
+        &self.timeline
+    }
+
+    /// Applies the timeline as of `now`. Once it's done, its last values are
+    /// applied just the once, leaving the properties to other animations
+    /// unless the clock is sought back into it.
+    pub(crate) fn apply(&mut self, now: Duration, props: &mut DrawProps) {
+        let start = *self.start.get_or_insert(now);
+        let t = now.checked_sub(start).unwrap_or_default();
+        let done = self.timeline.is_done(t);
+        if !(done && self.finished) {
+            self.timeline.apply(t, props);
+        }
+        self.finished = done;
+    }
+
+    pub(crate) fn is_done(&self, now: Duration) -> bool {
+        match self.start {
+            Some(start) => {
+                let t = now.checked_sub(start).unwrap_or_default();
+                self.timeline.is_done(t)
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Keyframe, Timeline, Track};
+use crate::prelude::*;
+use skia::Color4f;
+
+#[derive(Debug)]
+pub enum TimelineError {
+    IoError(IoError),
+    #[cfg(feature = "timeline-json")]
+    JsonError(serde_json::Error),
+    #[cfg(feature = "timeline-ron")]
+    RonError(ron::Error),
+    /// The file extension isn't one of the enabled formats.
+    UnknownFormat(String),
+    /// A keyframe's time, in seconds, isn't a number or is too large to
+    /// hold.
+    InvalidTime(f64),
+}
+
+impl Display for TimelineError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            TimelineError::IoError(e) => {
+                write!(f, "reading timeline failed: {}", e)
+            }
+            #[cfg(feature = "timeline-json")]
+            TimelineError::JsonError(e) => {
+                write!(f, "invalid JSON timeline: {}", e)
+            }
+            #[cfg(feature = "timeline-ron")]
+            TimelineError::RonError(e) => {
+                write!(f, "invalid RON timeline: {}", e)
+            }
+            TimelineError::UnknownFormat(s) => {
+                write!(f, "unknown timeline format \"{}\"", s)
+            }
+            TimelineError::InvalidTime(t) => {
+                write!(f, "invalid keyframe time {}", t)
+            }
+        }
+    }
+}
+
+impl StdError for TimelineError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            TimelineError::IoError(e) => Some(e),
+            #[cfg(feature = "timeline-json")]
+            TimelineError::JsonError(e) => Some(e),
+            #[cfg(feature = "timeline-ron")]
+            TimelineError::RonError(e) => Some(e),
+            TimelineError::UnknownFormat(_) | TimelineError::InvalidTime(_) => {
+                None
+            }
+        }
+    }
+}
+
+impl Timeline {
+    /// Loads a timeline from a file, going by its extension: `.json` needs
+    /// the `timeline-json` feature, `.ron` the `timeline-ron` feature.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TimelineError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let read =
+            || std::fs::read_to_string(path).map_err(TimelineError::IoError);
+        match extension.as_str() {
+            #[cfg(feature = "timeline-json")]
+            "json" => Self::from_json(&read()?),
+            #[cfg(feature = "timeline-ron")]
+            "ron" => Self::from_ron(&read()?),
+            _ => Err(TimelineError::UnknownFormat(extension)),
+        }
+    }
+
+    #[cfg(feature = "timeline-json")]
+    pub fn from_json(s: &str) -> Result<Self, TimelineError> {
+        let file: TimelineFile =
+            serde_json::from_str(s).map_err(TimelineError::JsonError)?;
+        file.into_timeline()
+    }
+
+    #[cfg(feature = "timeline-json")]
+    pub fn to_json(&self) -> Result<String, TimelineError> {
+        serde_json::to_string_pretty(&TimelineFile::from(self))
+            .map_err(TimelineError::JsonError)
+    }
+
+    #[cfg(feature = "timeline-ron")]
+    pub fn from_ron(s: &str) -> Result<Self, TimelineError> {
+        let file: TimelineFile =
+            ron::de::from_str(s).map_err(TimelineError::RonError)?;
+        file.into_timeline()
+    }
+
+    #[cfg(feature = "timeline-ron")]
+    pub fn to_ron(&self) -> Result<String, TimelineError> {
+        let config = ron::ser::PrettyConfig::new();
+        ron::ser::to_string_pretty(&TimelineFile::from(self), config)
+            .map_err(TimelineError::RonError)
+    }
+}
+
+/// How timelines are stored. Times are in seconds, and values are plain
+/// arrays: `[x, y]` for vectors and sizes, `[r, g, b, a]` for colours and
+/// the nine values of a matrix, row by row. Easings are [EasingFn] values,
+/// like `"OutCubic"`, and are linear if left out.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TimelineFile {
+    looping: bool,
+    alpha: Vec<KeyframeFile<scalar>>,
+    position: Vec<KeyframeFile<[scalar; 2]>>,
+    scale: Vec<KeyframeFile<[scalar; 2]>>,
+    rotation: Vec<KeyframeFile<scalar>>,
+    colour: Vec<KeyframeFile<[scalar; 4]>>,
+    size: Vec<KeyframeFile<[scalar; 2]>>,
+    transform: Vec<KeyframeFile<[scalar; 9]>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyframeFile<T> {
+    time: f64,
+    value: T,
+    #[serde(default)]
+    easing: EasingFn,
+}
+
+/// Conversion between track values and how they're stored.
+trait FileValue: Sized {
+    type Stored: Copy;
+
+    fn from_stored(v: Self::Stored) -> Self;
+
+    fn to_stored(&self) -> Self::Stored;
+}
+
+impl FileValue for scalar {
+    type Stored = scalar;
+
+    fn from_stored(v: scalar) -> Self {
+        v
+    }
+
+    fn to_stored(&self) -> scalar {
+        *self
+    }
+}
+
+impl FileValue for Vector {
+    type Stored = [scalar; 2];
+
+    fn from_stored(v: [scalar; 2]) -> Self {
+        Vector::new(v[0], v[1])
+    }
+
+    fn to_stored(&self) -> [scalar; 2] {
+        [self.x, self.y]
+    }
+}
+
+impl FileValue for Size {
+    type Stored = [scalar; 2];
+
+    fn from_stored(v: [scalar; 2]) -> Self {
+        Size::new(v[0], v[1])
+    }
+
+    fn to_stored(&self) -> [scalar; 2] {
+        [self.width, self.height]
+    }
+}
+
+impl FileValue for Color4f {
+    type Stored = [scalar; 4];
+
+    fn from_stored(v: [scalar; 4]) -> Self {
+        Color4f::new(v[0], v[1], v[2], v[3])
+    }
+
+    fn to_stored(&self) -> [scalar; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+impl FileValue for Matrix {
+    type Stored = [scalar; 9];
+
+    fn from_stored(v: [scalar; 9]) -> Self {
+        let mut m = Matrix::default();
+        m.set_9(&v);
+        m
+    }
+
+    fn to_stored(&self) -> [scalar; 9] {
+        let mut v = [0.0; 9];
+        self.get_9(&mut v);
+        v
+    }
+}
+
+/// Turns a keyframe time in seconds into a [Duration], with negative times
+/// counting as zero.
+fn time_from_file(time: f64) -> Result<Duration, TimelineError> {
+    // `Duration::from_secs_f64` panics on anything it can't hold.
+    if time.is_nan() || time >= u64::MAX as f64 {
+        return Err(TimelineError::InvalidTime(time));
+    }
+    Ok(Duration::from_secs_f64(time.max(0.0)))
+}
+
+fn track_from_file<T>(
+    keyframes: Vec<KeyframeFile<T::Stored>>,
+) -> Result<Track<T>, TimelineError>
+where
+    T: FileValue + Lerp + Copy,
+{
+    let mut track = Track::new();
+    for k in keyframes {
+        track.insert(Keyframe {
+            time: time_from_file(k.time)?,
+            value: T::from_stored(k.value),
+            easing: k.easing,
+        });
+    }
+    Ok(track)
+}
+
+fn track_to_file<T>(track: &Track<T>) -> Vec<KeyframeFile<T::Stored>>
+where
+    T: FileValue + Lerp + Copy,
+{
+    track
+        .keyframes()
+        .iter()
+        .map(|k| KeyframeFile {
+            time: k.time.as_secs_f64(),
+            value: k.value.to_stored(),
+            easing: k.easing,
+        })
+        .collect()
+}
+
+impl TimelineFile {
+    fn into_timeline(self) -> Result<Timeline, TimelineError> {
+        Ok(Timeline {
+            alpha: track_from_file(self.alpha)?,
+            position: track_from_file(self.position)?,
+            scale: track_from_file(self.scale)?,
+            rotation: track_from_file(self.rotation)?,
+            colour: track_from_file(self.colour)?,
+            size: track_from_file(self.size)?,
+            transform: track_from_file(self.transform)?,
+            looping: self.looping,
+        })
+    }
+}
+
+impl From<&Timeline> for TimelineFile {
+    fn from(timeline: &Timeline) -> Self {
+        Self {
+            looping: timeline.looping,
+            alpha: track_to_file(&timeline.alpha),
+            position: track_to_file(&timeline.position),
+            scale: track_to_file(&timeline.scale),
+            rotation: track_to_file(&timeline.rotation),
+            colour: track_to_file(&timeline.colour),
+            size: track_to_file(&timeline.size),
+            transform: track_to_file(&timeline.transform),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use super::{
+    layout::{Margin, MarginContainer},
+    shapes::Rectangle,
+    Backgrounded, FontStyle, Text, TextLayoutMode,
+};
+use crate::framework::overlay::{
+    Anchor, OverlayAlign, OverlayHandle, OverlayOptions, Placement,
+};
+use crate::prelude::*;
+use skia::Point;
+
+type Bubble = Backgrounded<Rectangle, MarginContainer<Text>>;
+
+/// Shows a line of text in a popup once the pointer has rested on a widget
+/// for a while.
+///
+/// The popup opens just below the pointer, moving to stay inside the
+/// window, and hides as soon as the pointer moves or anything is clicked.
+/// After a click on the widget, it won't show again until the pointer leaves
+/// the widget and comes back.
+pub struct Tooltip<T: Widget + ?Sized> {
+    child: Wrap<T>,
+    bubble: Wrap<Bubble>,
+    label: Wrap<Text>,
+    delay: Duration,
+    resources: Option<ResourceStack>,
+    resting_since: Option<Duration>,
+    pointer: Point,
+    suppressed: bool,
+    handle: Option<OverlayHandle>,
+}
+
+impl<T: Widget + ?Sized> Tooltip<T> {
+    const DEFAULT_DELAY: Duration = Duration::from_millis(600);
+    const POINTER_GAP: scalar = 16.0;
+
+    pub fn new(
+        child: Wrap<T>,
+        text: impl AsRef<str>,
+        background: Paint,
+        text_paint: Paint,
+    ) -> Wrap<Self> {
+        FrameworkState::request_load();
+        let label = Text::new(
+            LayoutSize::ZERO,
+            Some(TextLayoutMode::OneLine),
+            text,
+            None,
+            FontStyle::Regular,
+            Some(14.0),
+            text_paint,
+        );
+        let rect = Rectangle::new(
+            LayoutSize::ZERO.expand_width().expand_height(),
+            background,
+        );
+        let margin = MarginContainer::new(label.clone(), Margin::all(6.0));
+        Self {
+            child,
+            bubble: Backgrounded::new(rect, margin, false),
+            label,
+            delay: Self::DEFAULT_DELAY,
+            resources: None,
+            resting_since: None,
+            pointer: Point::default(),
+            suppressed: false,
+            handle: None,
+        }
+        .into()
+    }
+
+    /// Sets how long the pointer has to rest before the tooltip shows.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.label.inner_mut().set_text(text);
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.handle.as_ref().map_or(false, OverlayHandle::is_open)
+    }
+
+    fn hide(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.close();
+        }
+    }
+
+    fn show(&mut self, state: &WidgetState) {
+        let anchor = state.matrix().map_point(self.pointer);
+        let mut options = OverlayOptions::popup()
+            .placement(Placement::Below)
+            .align(OverlayAlign::Start)
+            .gap(Self::POINTER_GAP);
+        options.dismiss_on_outside_click = false;
+        options.resources = self.resources.as_ref().map(|r| r.snapshot());
+        self.handle = Some(FrameworkState::open_overlay(
+            self.bubble.clone(),
+            Anchor::Point(anchor),
+            options,
+        ));
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for Tooltip<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.resources = Some(stack.snapshot());
+        self.child.load(stack);
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.child.update();
+    }
+
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        let taken = self.child.input(event);
+        match event {
+            InputEvent::MouseMove(p) => {
+                self.hide();
+                self.pointer = *p;
+                self.resting_since = Some(State::elapsed_draw());
+                taken || Rect::from_size(state.current_size()).contains(*p)
+            }
+            InputEvent::MouseDown(_, p) => {
+                self.hide();
+                // Containers pass presses elsewhere on to every child, so
+                // only those on this widget keep the tooltip away.
+                if Rect::from_size(state.current_size()).contains(*p) {
+                    self.suppressed = true;
+                }
+                taken
+            }
+            _ => taken,
+        }
+    }
+
+    fn hover(&mut self, _state: &mut WidgetState) {
+        self.suppressed = false;
+    }
+
+    fn hover_lost(&mut self, _state: &mut WidgetState) {
+        self.hide();
+        self.resting_since = None;
+        self.suppressed = false;
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        self.child.size()
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.child.set_size(size);
+    }
+
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
+        self.child.draw(canvas);
+        if let Some(since) = self.resting_since {
+            let rested = State::elapsed_draw().checked_sub(since);
+            let due = rested.map_or(false, |t| t >= self.delay);
+            if due && state.is_hovered() && !self.suppressed {
+                self.resting_since = None;
+                self.show(state);
+            }
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("text", self.label.inner().get_text().clone()),
+            ("shown", self.is_shown().to_string()),
+        ]
+    }
+}
@@ -45,4 +45,12 @@ impl<T: Widget + ?Sized> Widget for Transform<T> {
         self.child.draw(canvas);
         canvas.restore();
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("matrix", format!("{:?}", self.matrix))]
+    }
 }
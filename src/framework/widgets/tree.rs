@@ -0,0 +1,271 @@
+use super::{LayoutDimension, LayoutSize};
+use crate::game::ID;
+use crate::skia::{scalar, Point, Rect, Size};
+
+/// A widget in the widget tree, as seen from the outside.
+///
+/// This is implemented by `Wrap<T>` for every `T`, and is what
+/// [Widget::visit_children](super::Widget::visit_children) hands out, so the
+/// tree can be walked without knowing the concrete type of any widget.
+///
+/// None of these methods may be called while the widget is being borrowed,
+/// which notably means the tree can't be inspected from inside itself.
+pub trait WidgetNode {
+    /// Returns a snapshot of this widget's state.
+    fn info(&self) -> WidgetInfo;
+
+    /// Calls `f` on every direct child of this widget, in drawing order.
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode));
+}
+
+/// A snapshot of a widget's state, as returned by [WidgetNode::info].
+#[derive(Clone, Debug)]
+pub struct WidgetInfo {
+    pub id: ID,
+    pub type_name: &'static str,
+    pub layout_size: LayoutSize,
+    pub size: Size,
+    /// The widget's origin in window coordinates, as of its last draw.
+    pub position: Point,
+    /// The widget's bounds in window coordinates, as of its last draw.
+    pub bounds: Rect,
+    pub is_hovered: bool,
+    pub is_focused: bool,
+    pub properties: Vec<(&'static str, String)>,
+}
+
+impl WidgetInfo {
+    /// The widget's type name, stripped of module paths.
+    pub fn short_type_name(&self) -> String {
+        short_type_name(self.type_name)
+    }
+}
+
+/// An owned copy of a widget subtree, useful for snapshot testing layouts and
+/// attaching to crash reports.
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    pub info: WidgetInfo,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Captures the subtree starting at `node`.
+    pub fn capture(node: &dyn WidgetNode) -> Self {
+        let mut children = vec![];
+        node.visit_children(&mut |child: &dyn WidgetNode| {
+            children.push(Self::capture(child))
+        });
+        Self {
+            info: node.info(),
+            children,
+        }
+    }
+
+    /// Finds the node with the given ID in this subtree.
+    pub fn find(&self, id: ID) -> Option<&TreeNode> {
+        if self.info.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|c| c.find(id))
+    }
+
+    /// Finds the widget with the given ID in the subtree starting at `node`,
+    /// without capturing the whole subtree.
+    pub fn find_info(node: &dyn WidgetNode, id: ID) -> Option<WidgetInfo> {
+        Self::find_info_by(node, &mut |info: &WidgetInfo| info.id == id)
+    }
+
+    /// Finds the first widget in the subtree starting at `node` for which `f`
+    /// returns `true`, searching depth-first.
+    pub fn find_info_by(
+        node: &dyn WidgetNode,
+        f: &mut dyn FnMut(&WidgetInfo) -> bool,
+    ) -> Option<WidgetInfo> {
+        let info = node.info();
+        if f(&info) {
+            return Some(info);
+        }
+        let mut found = None;
+        node.visit_children(&mut |child: &dyn WidgetNode| {
+            if found.is_none() {
+                found = Self::find_info_by(child, f);
+            }
+        });
+        found
+    }
+
+    /// Serializes this subtree to indented text, one widget per line.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0);
+        out
+    }
+
+    /// Serializes this subtree to JSON.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let i = &self.info;
+        for _ in 0..depth {
+            out.push_str("  ");
+        }
+        out.push_str(&format!(
+            "{} {} layout=({}, {}) size={}x{} at ({}, {})",
+            i.short_type_name(),
+            i.id,
+            dimension_text(&i.layout_size.width),
+            dimension_text(&i.layout_size.height),
+            i.size.width,
+            i.size.height,
+            i.position.x,
+            i.position.y,
+        ));
+        if i.is_hovered {
+            out.push_str(" hovered");
+        }
+        if i.is_focused {
+            out.push_str(" focused");
+        }
+        for (name, value) in &i.properties {
+            out.push_str(&format!(" {}={:?}", name, value));
+        }
+        out.push('\n');
+        for child in &self.children {
+            child.write_text(out, depth + 1);
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let i = &self.info;
+        out.push_str("{\"type\":");
+        write_json_string(out, &i.short_type_name());
+        out.push_str(",\"full_type\":");
+        write_json_string(out, i.type_name);
+        out.push_str(&format!(",\"id\":{}", i.id.as_u64()));
+        out.push_str(",\"layout_size\":{\"width\":");
+        write_json_dimension(out, &i.layout_size.width);
+        out.push_str(",\"height\":");
+        write_json_dimension(out, &i.layout_size.height);
+        out.push_str("},\"size\":[");
+        write_json_number(out, i.size.width);
+        out.push(',');
+        write_json_number(out, i.size.height);
+        out.push_str("],\"position\":[");
+        write_json_number(out, i.position.x);
+        out.push(',');
+        write_json_number(out, i.position.y);
+        out.push_str("],\"bounds\":[");
+        let b = &i.bounds;
+        for (n, v) in [b.left, b.top, b.right, b.bottom].iter().enumerate() {
+            if n != 0 {
+                out.push(',');
+            }
+            write_json_number(out, *v);
+        }
+        out.push_str(&format!(
+            "],\"hovered\":{},\"focused\":{},\"properties\":{{",
+            i.is_hovered, i.is_focused
+        ));
+        for (n, (name, value)) in i.properties.iter().enumerate() {
+            if n != 0 {
+                out.push(',');
+            }
+            write_json_string(out, name);
+            out.push(':');
+            write_json_string(out, value);
+        }
+        out.push_str("},\"children\":[");
+        for (n, child) in self.children.iter().enumerate() {
+            if n != 0 {
+                out.push(',');
+            }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+}
+
+fn dimension_text(d: &LayoutDimension) -> String {
+    let mut s = format!("{}", d.min);
+    if let Some(e) = d.expand {
+        s.push_str(&format!(" expand {}", e));
+    }
+    if let Some(m) = d.max {
+        s.push_str(&format!(" max {}", m));
+    }
+    if let Some(r) = d.relative {
+        s.push_str(&format!(" relative {}", r));
+    }
+    s
+}
+
+fn write_json_dimension(out: &mut String, d: &LayoutDimension) {
+    out.push_str("{\"min\":");
+    write_json_number(out, d.min);
+    for (name, value) in &[
+        ("expand", d.expand),
+        ("max", d.max),
+        ("relative", d.relative),
+    ] {
+        out.push_str(&format!(",\"{}\":", name));
+        match value {
+            Some(v) => write_json_number(out, *v),
+            None => out.push_str("null"),
+        }
+    }
+    out.push('}');
+}
+
+pub(crate) fn write_json_number(out: &mut String, n: scalar) {
+    // JSON has no representation for infinities or NaNs.
+    if n.is_finite() {
+        out.push_str(&n.to_string());
+    } else {
+        out.push_str("null");
+    }
+}
+
+pub(crate) fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+pub(crate) fn short_type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut start = 0;
+    for (i, c) in name.char_indices() {
+        let delimiter = matches!(
+            c,
+            '<' | '>' | ',' | ' ' | '(' | ')' | '[' | ']' | '&' | ';'
+        );
+        if delimiter {
+            out.push_str(last_path_segment(&name[start..i]));
+            out.push(c);
+            start = i + c.len_utf8();
+        }
+    }
+    out.push_str(last_path_segment(&name[start..]));
+    out
+}
+
+fn last_path_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
@@ -1,7 +1,13 @@
 mod button;
+mod dialog;
+mod menu;
 mod slider;
+mod tab_container;
 mod textedit;
 
 pub use button::Button;
+pub use dialog::Dialog;
+pub use menu::{Accelerator, ContextMenu, Menu, MenuBar, MenuStyle};
 pub use slider::{Slider, ValueRange};
+pub use tab_container::{TabContainer, TabContainerDyn, TabStyle};
 pub use textedit::TextEdit;
@@ -1,17 +1,20 @@
 use super::super::{
     layout::{Margin, MarginContainer},
     shapes::Rectangle,
-    FontName, FontStyle, Text, TextLayoutMode,
+    FontName, FontStyle, Text, TextLayoutMode, Tweened,
 };
 use crate::prelude::*;
 
+use std::time::Duration;
+
 pub struct Button {
     rect: Wrap<Rectangle>,
     label: Wrap<MarginContainer<Text>>,
 
     size: Size,
-    glow: scalar,
+    glow: Tweened<scalar>,
     glow_paint: Paint,
+    focused: bool,
 
     on_click_fns: Vec<Box<dyn FnMut()>>,
 }
@@ -20,6 +23,7 @@ impl Button {
     const HOVER_GLOW: scalar = 0.3;
     const FULL_GLOW: scalar = 1.0;
     const GLOW_TO_ALPHA_MUL: scalar = 0.25;
+    const GLOW_PER_SECOND: scalar = 4.0;
 
     pub fn new(
         label: String,
@@ -50,9 +54,10 @@ impl Button {
             rect,
             label,
             size: Size::default(),
-            glow: 0.0,
+            glow: Tweened::new(0.0),
             glow_paint: Paint::new_color4f(1.0, 1.0, 1.0, 1.0)
                 .with_anti_alias(label_aa),
+            focused: false,
             on_click_fns: vec![],
         }
         .wrap()
@@ -64,6 +69,19 @@ impl Button {
     pub fn on_click<F: FnMut() + 'static>(&mut self, f: F) {
         self.on_click_fns.push(Box::new(f));
     }
+
+    /// Highlights the button as if hovered, for keyboard navigation.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Presses the button, as if clicked.
+    pub fn click(&mut self) {
+        self.glow.set(Self::FULL_GLOW);
+        for f in &mut self.on_click_fns {
+            f();
+        }
+    }
 }
 
 impl Widget for Button {
@@ -90,10 +108,7 @@ impl Widget for Button {
             }
             InputEvent::MouseUp(MouseButton::Left, position) => {
                 if r.contains(*position) {
-                    self.glow = Self::FULL_GLOW;
-                    for f in &mut self.on_click_fns {
-                        f();
-                    }
+                    self.click();
                     return true;
                 }
             }
@@ -115,20 +130,31 @@ impl Widget for Button {
     fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
         self.rect.draw(canvas);
 
-        let t = State::last_update_time_draw().as_secs_f32() * 4.0;
-        if state.is_hovered() {
-            if self.glow > Self::HOVER_GLOW {
-                self.glow = (self.glow - t).max(Self::HOVER_GLOW);
-            } else {
-                self.glow = (self.glow + t).min(Self::HOVER_GLOW);
-            }
+        let now = state.elapsed();
+        let target = if state.is_hovered() || self.focused {
+            Self::HOVER_GLOW
         } else {
-            self.glow = (self.glow - t).max(0.0);
+            0.0
+        };
+        if self.glow.target() != target {
+            let distance = (self.glow.get() - target).abs();
+            let duration =
+                Duration::from_secs_f32(distance / Self::GLOW_PER_SECOND);
+            self.glow.tween_to(target, now, duration, |t| t);
         }
-        self.glow_paint
-            .set_alpha_f(self.glow * Self::GLOW_TO_ALPHA_MUL);
+        let glow = self.glow.update(now);
+        self.glow_paint.set_alpha_f(glow * Self::GLOW_TO_ALPHA_MUL);
         canvas.draw_rect(Rect::from_size(self.size), &self.glow_paint);
 
         self.label.draw(canvas);
     }
+
+    fn baseline(&self, _state: &WidgetState) -> Option<scalar> {
+        self.label.baseline()
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.rect);
+        f(&self.label);
+    }
 }
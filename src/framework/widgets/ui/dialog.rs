@@ -0,0 +1,263 @@
+use super::super::{
+    layout::{
+        ContainerSize, HContainer, Margin, MarginContainer, VContainerDyn,
+    },
+    FontStyle, Text, TextLayoutMode,
+};
+use super::Button;
+use crate::framework::overlay::{Anchor, OverlayHandle, OverlayOptions};
+use crate::game::ID;
+use crate::prelude::*;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// A modal dialog with a title, some content and a row of buttons, shown
+/// centered over a dimmed backdrop.
+///
+/// While open, it takes all keyboard input: Tab and Shift+Tab move between
+/// buttons, Enter presses the highlighted one and Escape closes the dialog
+/// without a choice. The result is given to the [Dialog::on_result]
+/// callbacks as the index of the pressed button.
+///
+/// ```ignore
+/// let body = Text::new(/* ... */);
+/// let mut dialog = Dialog::new("Quit?", body, &["Cancel", "Quit"], /* ... */);
+/// dialog.inner_mut().on_result(|r| {
+///     if r == Some(1) {
+///         // Quit
+///     }
+/// });
+/// Dialog::open(&dialog, Some(resources.snapshot()));
+/// ```
+pub struct Dialog {
+    pub background_paint: Paint,
+    pub corner_radius: scalar,
+    content: Wrap<MarginContainer<VContainerDyn>>,
+    buttons: Vec<Wrap<Button>>,
+    chosen: Rc<Cell<Option<usize>>>,
+    focused: usize,
+    handle: Option<OverlayHandle>,
+    /// What had focus before the dialog opened, given back once it closes.
+    previous_focus: Option<ID>,
+    on_result_fns: Vec<Box<dyn FnMut(Option<usize>)>>,
+    size: Size,
+}
+
+impl Dialog {
+    const MIN_WIDTH: scalar = 320.0;
+    const SPACING: scalar = 12.0;
+
+    pub fn new<T: Widget + 'static>(
+        title: impl AsRef<str>,
+        body: Wrap<T>,
+        buttons: &[&str],
+        background: Paint,
+        button_paint: Paint,
+        text_paint: Paint,
+    ) -> Wrap<Self> {
+        FrameworkState::request_load();
+        let title = Text::new(
+            LayoutSize::ZERO.expand_width(),
+            Some(TextLayoutMode::OneLine),
+            title,
+            None,
+            FontStyle::Bold,
+            Some(20.0),
+            text_paint.clone(),
+        );
+
+        let chosen = Rc::new(Cell::new(None));
+        let mut row = HContainer::new(ContainerSize::ZERO, Some(8.0));
+        let buttons: Vec<_> = buttons
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let mut button = Button::new(
+                    (*label).to_owned(),
+                    None,
+                    FontStyle::Regular,
+                    None,
+                    button_paint.clone(),
+                    text_paint.clone(),
+                );
+                let chosen = chosen.clone();
+                button.inner_mut().on_click(move || chosen.set(Some(i)));
+                row.inner_mut().add_child(button.clone());
+                button
+            })
+            .collect();
+
+        let mut column = VContainerDyn::new(
+            ContainerSize::min(Self::MIN_WIDTH, 0.0),
+            Some(Self::SPACING),
+        );
+        column
+            .inner_mut()
+            .add_child(title.into())
+            .add_child(body.into())
+            .add_child(row.into());
+        let content = MarginContainer::new(column, Margin::all(16.0));
+
+        let mut dialog = Self {
+            background_paint: background,
+            corner_radius: 6.0,
+            content,
+            buttons,
+            chosen,
+            focused: 0,
+            handle: None,
+            previous_focus: None,
+            on_result_fns: vec![],
+            size: Size::default(),
+        };
+        dialog.set_focus(0);
+        dialog.into()
+    }
+
+    /// Shows the dialog, loading it with `resources`, usually a
+    /// [ResourceStack::snapshot] taken by whatever opens it.
+    pub fn open(
+        dialog: &Wrap<Self>,
+        resources: Option<ResourceStack>,
+    ) -> OverlayHandle {
+        let mut options = OverlayOptions::modal();
+        options.resources = resources;
+        // Keyboard input goes to the dialog, not whatever had focus, until
+        // the dialog is closed.
+        let previous_focus = FrameworkState::current_focus();
+        FrameworkState::force_release_focus();
+        let handle = FrameworkState::open_overlay(
+            dialog.clone(),
+            Anchor::Centered,
+            options,
+        );
+        let mut dialog = dialog.clone();
+        let mut inner = dialog.inner_mut();
+        inner.chosen.set(None);
+        inner.handle = Some(handle.clone());
+        inner.previous_focus = previous_focus;
+        handle
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.handle.as_ref().map_or(false, OverlayHandle::is_open)
+    }
+
+    /// Closes the dialog without a choice, as if Escape was pressed.
+    pub fn close(&mut self) {
+        self.finish(None);
+    }
+
+    /// Adds a function called with the index of the button pressed, or
+    /// `None` if the dialog was closed otherwise.
+    ///
+    /// Callbacks run while the dialog is borrowed, so they mustn't borrow it
+    /// again through its `Wrap`. Keep whatever they need to change outside
+    /// the dialog, for example in an `Rc<Cell<_>>`.
+    pub fn on_result<F: FnMut(Option<usize>) + 'static>(&mut self, f: F) {
+        self.on_result_fns.push(Box::new(f));
+    }
+
+    fn set_focus(&mut self, i: usize) {
+        if self.buttons.is_empty() {
+            return;
+        }
+        self.focused = i % self.buttons.len();
+        for (j, button) in self.buttons.iter_mut().enumerate() {
+            button.inner_mut().set_focused(j == self.focused);
+        }
+    }
+
+    fn finish(&mut self, result: Option<usize>) {
+        let handle = match self.handle.take() {
+            Some(handle) => handle,
+            None => return,
+        };
+        handle.close();
+        if let Some(id) = self.previous_focus.take() {
+            if FrameworkState::current_focus().is_none() {
+                FrameworkState::grab_focus(id);
+            }
+        }
+        for f in &mut self.on_result_fns {
+            f(result);
+        }
+    }
+
+    fn check_chosen(&mut self) {
+        if let Some(i) = self.chosen.take() {
+            self.finish(Some(i));
+        }
+    }
+}
+
+impl Widget for Dialog {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.content.load(stack);
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.content.update();
+        self.check_chosen();
+    }
+
+    fn input(&mut self, _state: &mut WidgetState, event: &InputEvent) -> bool {
+        let key = match event {
+            InputEvent::Focused(_, inner) => inner.as_ref(),
+            event => event,
+        };
+        match key {
+            InputEvent::KeyDown(Keycode::Escape) => self.finish(None),
+            InputEvent::KeyDown(Keycode::Tab) => {
+                let count = self.buttons.len().max(1);
+                let back = State::is_key_down(Keycode::LShift)
+                    || State::is_key_down(Keycode::RShift);
+                let step = if back { count - 1 } else { 1 };
+                self.set_focus(self.focused + step);
+            }
+            InputEvent::KeyDown(Keycode::Return)
+            | InputEvent::KeyDown(Keycode::NumpadEnter) => {
+                if let Some(button) = self.buttons.get_mut(self.focused) {
+                    button.inner_mut().click();
+                }
+                self.check_chosen();
+            }
+            _ => {
+                self.content.input(event);
+                self.check_chosen();
+            }
+        }
+        true
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        self.content.size()
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        self.content.set_size(size);
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        canvas.draw_round_rect(
+            Rect::from_size(self.size),
+            self.corner_radius,
+            self.corner_radius,
+            &self.background_paint,
+        );
+        self.content.draw(canvas);
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.content);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("open", self.is_open().to_string()),
+            ("focused", self.focused.to_string()),
+        ]
+    }
+}
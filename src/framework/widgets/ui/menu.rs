@@ -0,0 +1,1142 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::rc::Rc;
+
+use super::super::{FontName, FontStyle, Text, TextLayoutMode};
+use crate::framework::overlay::{
+    Anchor, OverlayAlign, OverlayHandle, OverlayOptions, Placement,
+};
+use crate::prelude::*;
+use skia::Point;
+
+/// A key combination that triggers a menu item from anywhere, shown next to
+/// the item's label.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Accelerator {
+    pub key: Keycode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Accelerator {
+    pub const fn new(key: Keycode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(key: Keycode) -> Self {
+        Self::new(key).with_ctrl()
+    }
+
+    pub const fn with_ctrl(self) -> Self {
+        Self { ctrl: true, ..self }
+    }
+
+    pub const fn with_shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+
+    pub const fn with_alt(self) -> Self {
+        Self { alt: true, ..self }
+    }
+
+    /// Whether pressing `key` right now, with the modifiers currently held,
+    /// is this accelerator.
+    pub fn matches(&self, key: Keycode) -> bool {
+        let held = |l, r| State::is_key_down(l) || State::is_key_down(r);
+        key == self.key
+            && held(Keycode::LControl, Keycode::RControl) == self.ctrl
+            && held(Keycode::LShift, Keycode::RShift) == self.shift
+            && held(Keycode::LAlt, Keycode::RAlt) == self.alt
+    }
+}
+
+impl Display for Accelerator {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        let name = format!("{:?}", self.key);
+        match name.strip_prefix("Key") {
+            Some(digit) if digit.len() == 1 => write!(f, "{}", digit),
+            _ => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Takes key presses out of events sent to whatever has focus, so that
+/// accelerators keep working while, say, a text field is typed in. Anything
+/// else sent to another widget is left as is, to be ignored.
+fn unfocus_key(event: &InputEvent) -> &InputEvent {
+    match event {
+        InputEvent::Focused(_, inner)
+            if matches!(**inner, InputEvent::KeyDown(_)) =>
+        {
+            inner
+        }
+        event => event,
+    }
+}
+
+/// The letter or digit typed for a key, for matching mnemonics.
+fn key_char(key: Keycode) -> Option<char> {
+    let name = format!("{:?}", key);
+    let name = name.strip_prefix("Key").unwrap_or(&name);
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => {
+            Some(c.to_ascii_lowercase())
+        }
+        _ => None,
+    }
+}
+
+/// Splits a mnemonic, marked by a `&` before its character, out of `label`,
+/// giving its byte offset and lowercase character. `&&` stands for a plain
+/// `&`.
+fn parse_mnemonic(label: &str) -> (String, Option<(usize, char)>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('&') => text.push('&'),
+            Some(c) => {
+                if mnemonic.is_none() {
+                    mnemonic = Some((text.len(), c.to_ascii_lowercase()));
+                }
+                text.push(c);
+            }
+            None => {}
+        }
+    }
+    (text, mnemonic)
+}
+
+#[derive(Clone)]
+enum ItemKind {
+    Action(Rc<RefCell<dyn FnMut()>>),
+    Check(Rc<Cell<bool>>, Rc<RefCell<dyn FnMut(bool)>>),
+    Submenu(Menu),
+}
+
+#[derive(Clone)]
+struct MenuItem {
+    label: String,
+    /// Byte offset into `label` and the lowercase character.
+    mnemonic: Option<(usize, char)>,
+    accelerator: Option<Accelerator>,
+    enabled: bool,
+    kind: ItemKind,
+}
+
+impl MenuItem {
+    fn new(label: &str, kind: ItemKind) -> Self {
+        let (label, mnemonic) = parse_mnemonic(label);
+        Self {
+            label,
+            mnemonic,
+            accelerator: None,
+            enabled: true,
+            kind,
+        }
+    }
+
+    fn is_checked(&self) -> bool {
+        match &self.kind {
+            ItemKind::Check(checked, _) => checked.get(),
+            _ => false,
+        }
+    }
+
+    fn submenu(&self) -> Option<&Menu> {
+        match &self.kind {
+            ItemKind::Submenu(menu) => Some(menu),
+            _ => None,
+        }
+    }
+
+    fn activate(&self) {
+        match &self.kind {
+            ItemKind::Action(f) => (*f.borrow_mut())(),
+            ItemKind::Check(checked, f) => {
+                checked.set(!checked.get());
+                (*f.borrow_mut())(checked.get());
+            }
+            ItemKind::Submenu(_) => {}
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Entry {
+    Item(MenuItem),
+    Separator,
+}
+
+impl Entry {
+    fn item(&self) -> Option<&MenuItem> {
+        match self {
+            Entry::Item(item) => Some(item),
+            Entry::Separator => None,
+        }
+    }
+
+    fn is_selectable(&self) -> bool {
+        self.item().map_or(false, |item| item.enabled)
+    }
+}
+
+/// A list of menu items, shown by a [MenuBar] or [ContextMenu], or opened
+/// directly with [Menu::popup].
+///
+/// Labels can mark a mnemonic with `&`, as in `"&Save"`, which picks the item
+/// by typing its underlined character while the menu is open.
+///
+/// ```ignore
+/// let file = Menu::new()
+///     .item("&Open", || open())
+///     .accelerator(Accelerator::ctrl(Keycode::O))
+///     .item("&Save", || save())
+///     .accelerator(Accelerator::ctrl(Keycode::S))
+///     .disabled()
+///     .separator()
+///     .checkable("&Autosave", true, |on| set_autosave(on))
+///     .submenu("&Recent", recent);
+/// ```
+#[derive(Clone, Default)]
+pub struct Menu {
+    entries: Vec<Entry>,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item<F: FnMut() + 'static>(self, label: &str, f: F) -> Self {
+        let f: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(f));
+        self.with_item(MenuItem::new(label, ItemKind::Action(f)))
+    }
+
+    /// Adds an item with a check mark, toggled when picked. The function is
+    /// called with the new state.
+    pub fn checkable<F: FnMut(bool) + 'static>(
+        self,
+        label: &str,
+        checked: bool,
+        f: F,
+    ) -> Self {
+        let f: Rc<RefCell<dyn FnMut(bool)>> = Rc::new(RefCell::new(f));
+        let checked = Rc::new(Cell::new(checked));
+        self.with_item(MenuItem::new(label, ItemKind::Check(checked, f)))
+    }
+
+    pub fn submenu(self, label: &str, menu: Menu) -> Self {
+        self.with_item(MenuItem::new(label, ItemKind::Submenu(menu)))
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.entries.push(Entry::Separator);
+        self
+    }
+
+    /// Gives the last item an accelerator.
+    pub fn accelerator(mut self, accelerator: Accelerator) -> Self {
+        if let Some(Entry::Item(item)) = self.entries.last_mut() {
+            item.accelerator = Some(accelerator);
+        }
+        self
+    }
+
+    /// Disables the last item.
+    pub fn disabled(mut self) -> Self {
+        if let Some(Entry::Item(item)) = self.entries.last_mut() {
+            item.enabled = false;
+        }
+        self
+    }
+
+    fn with_item(mut self, item: MenuItem) -> Self {
+        self.entries.push(Entry::Item(item));
+        self
+    }
+
+    /// The number of entries, separators included.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Enables or disables the item at `index`. Menus already open keep
+    /// their state until opened again.
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(Entry::Item(item)) = self.entries.get_mut(index) {
+            item.enabled = enabled;
+        }
+    }
+
+    /// Whether the checkable item at `index` is checked.
+    pub fn is_checked(&self, index: usize) -> bool {
+        self.entries
+            .get(index)
+            .and_then(Entry::item)
+            .map_or(false, MenuItem::is_checked)
+    }
+
+    /// Checks or unchecks the checkable item at `index`, without calling its
+    /// function.
+    pub fn set_checked(&self, index: usize, checked: bool) {
+        let item = self.entries.get(index).and_then(Entry::item);
+        if let Some(ItemKind::Check(c, _)) = item.map(|item| &item.kind) {
+            c.set(checked);
+        }
+    }
+
+    /// Picks the enabled item with an accelerator matching `key`, searching
+    /// submenus too. Returns whether one was found.
+    pub fn trigger_accelerator(&self, key: Keycode) -> bool {
+        for item in self.entries.iter().filter_map(Entry::item) {
+            if !item.enabled {
+                continue;
+            }
+            if item.accelerator.map_or(false, |a| a.matches(key)) {
+                item.activate();
+                return true;
+            }
+            let submenu = item.submenu();
+            if submenu.map_or(false, |m| m.trigger_accelerator(key)) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Opens the menu in an overlay, such as at the pointer for a context
+    /// menu. `resources` are what the menu's text is loaded with, usually a
+    /// [ResourceStack::snapshot] taken by the opener.
+    pub fn popup(
+        &self,
+        anchor: Anchor,
+        style: &MenuStyle,
+        resources: Option<ResourceStack>,
+    ) -> OverlayHandle {
+        let session = Rc::new(Session::default());
+        let popup = MenuPopup::new(self.clone(), style.clone(), session, 0);
+        popup.open(anchor, Placement::Below, resources)
+    }
+}
+
+/// How menus are drawn.
+#[derive(Clone)]
+pub struct MenuStyle {
+    pub background: Paint,
+    /// Behind the highlighted item or open menu.
+    pub highlight: Paint,
+    pub text: Paint,
+    pub disabled_text: Paint,
+    pub separator: Paint,
+    pub font: Option<FontName>,
+    pub font_size: Option<scalar>,
+}
+
+impl MenuStyle {
+    /// A style with disabled text and separators drawn as faded `text`.
+    pub fn new(background: Paint, highlight: Paint, text: Paint) -> Self {
+        let faded = |alpha| {
+            let mut paint = text.clone();
+            paint.set_alpha_f(text.alpha_f() * alpha);
+            paint
+        };
+        Self {
+            background,
+            highlight,
+            disabled_text: faded(0.4),
+            separator: faded(0.2),
+            text,
+            font: None,
+            font_size: None,
+        }
+    }
+}
+
+/// The open popups of one menu, from the outermost one in.
+#[derive(Default)]
+struct Session {
+    handles: RefCell<Vec<OverlayHandle>>,
+    /// Set by a popup opened from a [MenuBar] to move to a neighbouring
+    /// menu with the arrow keys.
+    step: Cell<isize>,
+}
+
+impl Session {
+    fn push(&self, depth: usize, handle: OverlayHandle) {
+        self.close_from(depth);
+        self.handles.borrow_mut().push(handle);
+    }
+
+    fn is_open_at(&self, depth: usize) -> bool {
+        let handles = self.handles.borrow();
+        handles.get(depth).map_or(false, OverlayHandle::is_open)
+    }
+
+    fn close_from(&self, depth: usize) {
+        let mut handles = self.handles.borrow_mut();
+        for handle in handles.iter().skip(depth) {
+            handle.close();
+        }
+        handles.truncate(depth);
+    }
+}
+
+fn menu_text(text: &str, paint: &Paint, style: &MenuStyle) -> Wrap<Text> {
+    Text::new(
+        LayoutSize::ZERO,
+        Some(TextLayoutMode::OneLine),
+        text,
+        style.font.clone(),
+        FontStyle::Regular,
+        style.font_size,
+        paint.clone(),
+    )
+}
+
+/// Draws `text` vertically centered in `rect`, starting at `x`, with its
+/// mnemonic underlined.
+fn draw_menu_text(
+    canvas: &mut Canvas,
+    text: &mut Wrap<Text>,
+    size: Size,
+    rect: Rect,
+    x: scalar,
+    mnemonic: Option<usize>,
+    paint: &Paint,
+) {
+    let origin = Vector::new(x, rect.top + (rect.height() - size.height) * 0.5);
+    canvas.save();
+    canvas.translate(origin);
+    text.draw(canvas);
+    if let Some(offset) = mnemonic {
+        let text = text.inner();
+        let len = text.get_text()[offset..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        let from = text.grapheme_position(offset);
+        let to = text.grapheme_position(offset + len);
+        if let (Some(from), Some(to)) = (from, to) {
+            let y = from.y + 2.0;
+            canvas.draw_line((from.x, y), (to.x, y), paint);
+        }
+    }
+    canvas.restore();
+}
+
+struct Row {
+    label: Option<Wrap<Text>>,
+    hint: Option<Wrap<Text>>,
+    label_size: Size,
+    hint_size: Size,
+    rect: Rect,
+}
+
+/// One open level of a menu.
+struct MenuPopup {
+    menu: Menu,
+    style: MenuStyle,
+    session: Rc<Session>,
+    depth: usize,
+    in_bar: bool,
+    rows: Vec<Row>,
+    highlighted: Option<usize>,
+    /// The row whose submenu is open.
+    child: Option<usize>,
+    resources: Option<ResourceStack>,
+    size: Size,
+}
+
+impl MenuPopup {
+    const ROW_HEIGHT: scalar = 26.0;
+    const SEPARATOR_HEIGHT: scalar = 9.0;
+    const PADDING: scalar = 4.0;
+    const LABEL_INDENT: scalar = 24.0;
+    const HINT_GAP: scalar = 32.0;
+    const ARROW_SPACE: scalar = 20.0;
+    const MIN_WIDTH: scalar = 160.0;
+
+    fn new(
+        menu: Menu,
+        style: MenuStyle,
+        session: Rc<Session>,
+        depth: usize,
+    ) -> Self {
+        FrameworkState::request_load();
+        let rows = menu
+            .entries
+            .iter()
+            .map(|entry| {
+                let (label, hint) = match entry {
+                    Entry::Item(item) => {
+                        let paint = if item.enabled {
+                            &style.text
+                        } else {
+                            &style.disabled_text
+                        };
+                        let label = menu_text(&item.label, paint, &style);
+                        let hint = item
+                            .accelerator
+                            .map(|a| menu_text(&a.to_string(), paint, &style));
+                        (Some(label), hint)
+                    }
+                    Entry::Separator => (None, None),
+                };
+                Row {
+                    label,
+                    hint,
+                    label_size: Size::default(),
+                    hint_size: Size::default(),
+                    rect: Rect::default(),
+                }
+            })
+            .collect();
+        Self {
+            menu,
+            style,
+            session,
+            depth,
+            in_bar: false,
+            rows,
+            highlighted: None,
+            child: None,
+            resources: None,
+            size: Size::default(),
+        }
+    }
+
+    fn open(
+        self,
+        anchor: Anchor,
+        placement: Placement,
+        resources: Option<ResourceStack>,
+    ) -> OverlayHandle {
+        let session = self.session.clone();
+        let depth = self.depth;
+        let mut options = OverlayOptions::popup()
+            .placement(placement)
+            .align(OverlayAlign::Start);
+        options.resources = resources;
+        let handle = FrameworkState::open_overlay(self.wrap(), anchor, options);
+        session.push(depth, handle.clone());
+        handle
+    }
+
+    fn item(&self, i: usize) -> Option<&MenuItem> {
+        self.menu.entries.get(i).and_then(Entry::item)
+    }
+
+    fn row_at(&self, p: Point) -> Option<usize> {
+        self.rows.iter().position(|row| row.rect.contains(p))
+    }
+
+    /// Moves the highlight `step` selectable rows along, wrapping around.
+    fn move_highlight(&mut self, step: isize) {
+        let len = self.menu.len() as isize;
+        let mut i = match self.highlighted {
+            Some(i) => i as isize,
+            None if step > 0 => -1,
+            None => len,
+        };
+        for _ in 0..len {
+            i = (i + step).rem_euclid(len);
+            if self.menu.entries[i as usize].is_selectable() {
+                self.highlighted = Some(i as usize);
+                return;
+            }
+        }
+    }
+
+    fn open_child(&mut self, i: usize, state: &WidgetState, keyboard: bool) {
+        if self.child == Some(i) {
+            return;
+        }
+        self.close_child();
+        let submenu = match self.item(i).and_then(MenuItem::submenu) {
+            Some(submenu) => submenu.clone(),
+            None => return,
+        };
+        let mut popup = MenuPopup::new(
+            submenu,
+            self.style.clone(),
+            self.session.clone(),
+            self.depth + 1,
+        );
+        popup.in_bar = self.in_bar;
+        if keyboard {
+            popup.move_highlight(1);
+        }
+        let rect = state.matrix().map_rect(self.rows[i].rect).0;
+        let resources = self.resources.as_ref().map(|r| r.snapshot());
+        popup.open(Anchor::Rect(rect), Placement::Right, resources);
+        self.child = Some(i);
+    }
+
+    fn close_child(&mut self) {
+        self.session.close_from(self.depth + 1);
+        self.child = None;
+    }
+
+    fn activate(&mut self, i: usize, state: &WidgetState, keyboard: bool) {
+        let item = match self.item(i) {
+            Some(item) if item.enabled => item.clone(),
+            _ => return,
+        };
+        if item.submenu().is_some() {
+            self.highlighted = Some(i);
+            self.open_child(i, state, keyboard);
+        } else {
+            // Closed first, so the item is free to open something else.
+            self.session.close_from(0);
+            item.activate();
+        }
+    }
+
+    fn key(&mut self, key: Keycode, state: &WidgetState) {
+        match key {
+            Keycode::Up => self.move_highlight(-1),
+            Keycode::Down => self.move_highlight(1),
+            Keycode::Right => {
+                let submenu = self.highlighted.filter(|&i| {
+                    self.item(i).and_then(MenuItem::submenu).is_some()
+                });
+                match submenu {
+                    Some(i) => self.activate(i, state, true),
+                    None if self.in_bar => self.session.step.set(1),
+                    None => {}
+                }
+            }
+            Keycode::Left if self.depth > 0 => {
+                self.session.close_from(self.depth);
+            }
+            Keycode::Left if self.in_bar => self.session.step.set(-1),
+            Keycode::Return | Keycode::NumpadEnter | Keycode::Space => {
+                if let Some(i) = self.highlighted {
+                    self.activate(i, state, true);
+                }
+            }
+            Keycode::Escape => self.session.close_from(self.depth),
+            key => {
+                let c = key_char(key);
+                let found = self.menu.entries.iter().position(|e| {
+                    let item = e.item().filter(|item| item.enabled);
+                    let mnemonic = item.and_then(|item| item.mnemonic);
+                    c.is_some() && mnemonic.map(|m| m.1) == c
+                });
+                if let Some(i) = found {
+                    self.activate(i, state, true);
+                }
+            }
+        }
+    }
+}
+
+impl Widget for MenuPopup {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.resources = Some(stack.snapshot());
+        for row in &mut self.rows {
+            for text in row.label.iter_mut().chain(row.hint.iter_mut()) {
+                text.load(stack);
+            }
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        if !self.session.is_open_at(self.depth) {
+            // Whatever was opened from here goes too.
+            self.session.close_from(self.depth);
+        } else if self.child.is_some()
+            && !self.session.is_open_at(self.depth + 1)
+        {
+            self.child = None;
+        }
+    }
+
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        let event = unfocus_key(event);
+        if let InputEvent::Focused(..) = event {
+            return false;
+        }
+        let inside = event
+            .position()
+            .map_or(false, |p| Rect::from_size(self.size).contains(p));
+        match event {
+            InputEvent::MouseMove(p) => {
+                let row = self.row_at(*p);
+                if row.is_some() || self.child.is_none() {
+                    self.highlighted =
+                        row.filter(|&i| self.menu.entries[i].is_selectable());
+                }
+                match self.highlighted {
+                    Some(i) if row.is_some() => {
+                        if self.item(i).and_then(MenuItem::submenu).is_some() {
+                            self.open_child(i, state, false);
+                        } else {
+                            self.close_child();
+                        }
+                    }
+                    None if row.is_some() => self.close_child(),
+                    _ => {}
+                }
+                inside
+            }
+            InputEvent::MouseUp(MouseButton::Left, p) => {
+                if let Some(i) = self.row_at(*p) {
+                    self.activate(i, state, false);
+                }
+                inside
+            }
+            InputEvent::KeyDown(key) => {
+                self.key(*key, state);
+                true
+            }
+            _ => inside,
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut changed = false;
+        let mut label_width: scalar = 0.0;
+        let mut hint_width: scalar = 0.0;
+        let mut y = Self::PADDING;
+        for row in &mut self.rows {
+            if let Some(label) = &mut row.label {
+                let (size, c) = label.size();
+                changed |= c;
+                row.label_size = size.get_min();
+                label_width = label_width.max(row.label_size.width);
+            }
+            if let Some(hint) = &mut row.hint {
+                let (size, c) = hint.size();
+                changed |= c;
+                row.hint_size = size.get_min();
+                hint_width = hint_width.max(row.hint_size.width);
+            }
+            let height = if row.label.is_some() {
+                Self::ROW_HEIGHT
+            } else {
+                Self::SEPARATOR_HEIGHT
+            };
+            row.rect = Rect::from_xywh(0.0, y, 0.0, height);
+            y += height;
+        }
+        let hint_space = if hint_width > 0.0 {
+            Self::HINT_GAP + hint_width
+        } else {
+            0.0
+        };
+        let width =
+            Self::LABEL_INDENT + label_width + hint_space + Self::ARROW_SPACE;
+        let size =
+            LayoutSize::min(width.max(Self::MIN_WIDTH), y + Self::PADDING);
+        (size, changed)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        for row in &mut self.rows {
+            row.rect.right = size.width;
+            if let Some(label) = &mut row.label {
+                label.set_size(row.label_size);
+            }
+            if let Some(hint) = &mut row.hint {
+                hint.set_size(row.hint_size);
+            }
+        }
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        let style = &self.style;
+        canvas.draw_rect(Rect::from_size(self.size), &style.background);
+        let mut stroke = style.text.clone();
+        stroke.set_stroke_width(1.5);
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let rect = row.rect;
+            let mid = rect.center_y();
+            let item = match &self.menu.entries[i] {
+                Entry::Item(item) => item,
+                Entry::Separator => {
+                    let (l, r) =
+                        (rect.left + Self::PADDING, rect.right - Self::PADDING);
+                    canvas.draw_line((l, mid), (r, mid), &style.separator);
+                    continue;
+                }
+            };
+            if self.highlighted == Some(i) || self.child == Some(i) {
+                canvas.draw_rect(rect, &style.highlight);
+            }
+            let paint = if item.enabled {
+                &stroke
+            } else {
+                &style.disabled_text
+            };
+            if item.is_checked() {
+                let x = rect.left + 7.0;
+                canvas.draw_line((x, mid), (x + 3.0, mid + 3.0), paint);
+                canvas.draw_line(
+                    (x + 3.0, mid + 3.0),
+                    (x + 9.0, mid - 4.0),
+                    paint,
+                );
+            }
+            if item.submenu().is_some() {
+                let x = rect.right - 12.0;
+                canvas.draw_line((x, mid - 4.0), (x + 4.0, mid), paint);
+                canvas.draw_line((x + 4.0, mid), (x, mid + 4.0), paint);
+            }
+            if let Some(label) = &mut row.label {
+                let mnemonic = item.mnemonic.map(|m| m.0);
+                let size = row.label_size;
+                let x = rect.left + Self::LABEL_INDENT;
+                draw_menu_text(canvas, label, size, rect, x, mnemonic, paint);
+            }
+            if let Some(hint) = &mut row.hint {
+                let size = row.hint_size;
+                let x = rect.right - Self::ARROW_SPACE - size.width;
+                draw_menu_text(canvas, hint, size, rect, x, None, paint);
+            }
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for row in &self.rows {
+            for text in row.label.iter().chain(row.hint.iter()) {
+                f(text);
+            }
+        }
+    }
+}
+
+struct Title {
+    menu: Menu,
+    text: Wrap<Text>,
+    mnemonic: Option<(usize, char)>,
+    size: Size,
+    rect: Rect,
+}
+
+/// A row of menus along the top of a window.
+///
+/// Menus open by clicking their title, by Alt and a title's mnemonic, or
+/// with F10 for the first one. While one is open, the left and right arrow
+/// keys move between them. Accelerators of every menu work whenever no menu
+/// is open.
+pub struct MenuBar {
+    style: MenuStyle,
+    titles: Vec<Title>,
+    session: Rc<Session>,
+    open: Option<usize>,
+    hovered: Option<usize>,
+    resources: Option<ResourceStack>,
+    size: Size,
+}
+
+impl MenuBar {
+    const HEIGHT: scalar = 28.0;
+    const TITLE_PADDING: scalar = 10.0;
+
+    pub fn new(style: MenuStyle) -> Wrap<Self> {
+        FrameworkState::request_load();
+        Self {
+            style,
+            titles: vec![],
+            session: Rc::new(Session::default()),
+            open: None,
+            hovered: None,
+            resources: None,
+            size: Size::default(),
+        }
+        .into()
+    }
+
+    /// Adds a menu at the end of the bar. Like item labels, `title` can mark
+    /// a mnemonic with `&`.
+    pub fn add_menu(&mut self, title: &str, menu: Menu) -> &mut Self {
+        FrameworkState::request_load();
+        let (label, mnemonic) = parse_mnemonic(title);
+        self.titles.push(Title {
+            menu,
+            text: menu_text(&label, &self.style.text, &self.style),
+            mnemonic,
+            size: Size::default(),
+            rect: Rect::default(),
+        });
+        self
+    }
+
+    pub fn menu(&self, index: usize) -> Option<&Menu> {
+        self.titles.get(index).map(|t| &t.menu)
+    }
+
+    /// The menu at `index`, for changes that show the next time it opens.
+    pub fn menu_mut(&mut self, index: usize) -> Option<&mut Menu> {
+        self.titles.get_mut(index).map(|t| &mut t.menu)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Closes whichever menu is open.
+    pub fn close(&mut self) {
+        self.session.close_from(0);
+        self.open = None;
+    }
+
+    fn title_at(&self, p: Point) -> Option<usize> {
+        self.titles.iter().position(|t| t.rect.contains(p))
+    }
+
+    fn open_menu(&mut self, i: usize, state: &WidgetState, keyboard: bool) {
+        self.close();
+        let title = &self.titles[i];
+        let mut popup = MenuPopup::new(
+            title.menu.clone(),
+            self.style.clone(),
+            self.session.clone(),
+            0,
+        );
+        popup.in_bar = true;
+        if keyboard {
+            popup.move_highlight(1);
+        }
+        let rect = state.matrix().map_rect(title.rect).0;
+        let resources = self.resources.as_ref().map(|r| r.snapshot());
+        popup.open(Anchor::Rect(rect), Placement::Below, resources);
+        self.open = Some(i);
+    }
+}
+
+impl Widget for MenuBar {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.resources = Some(stack.snapshot());
+        for title in &mut self.titles {
+            title.text.load(stack);
+        }
+    }
+
+    fn update(&mut self, state: &mut WidgetState) {
+        if self.open.is_some() && !self.session.is_open_at(0) {
+            self.open = None;
+        }
+        let step = self.session.step.replace(0);
+        if let Some(i) = self.open.filter(|_| step != 0) {
+            let len = self.titles.len() as isize;
+            let next = (i as isize + step).rem_euclid(len);
+            self.open_menu(next as usize, state, true);
+        }
+    }
+
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        let event = unfocus_key(event);
+        match event {
+            InputEvent::MouseMove(p) => {
+                self.hovered = self.title_at(*p);
+                match (self.hovered, self.open) {
+                    (Some(i), Some(open)) if i != open => {
+                        self.open_menu(i, state, false);
+                    }
+                    _ => {}
+                }
+                self.hovered.is_some()
+            }
+            InputEvent::MouseDown(MouseButton::Left, p) => {
+                match self.title_at(*p) {
+                    Some(i) if self.open == Some(i) => self.close(),
+                    Some(i) => self.open_menu(i, state, false),
+                    None => return false,
+                }
+                true
+            }
+            InputEvent::KeyDown(Keycode::F10) if !self.titles.is_empty() => {
+                self.open_menu(0, state, true);
+                true
+            }
+            InputEvent::KeyDown(key) => {
+                let alt = State::is_key_down(Keycode::LAlt)
+                    || State::is_key_down(Keycode::RAlt);
+                let c = key_char(*key);
+                let found = self.titles.iter().position(|t| {
+                    alt && c.is_some() && t.mnemonic.map(|m| m.1) == c
+                });
+                if let Some(i) = found {
+                    self.open_menu(i, state, true);
+                    return true;
+                }
+                self.titles.iter().any(|t| t.menu.trigger_accelerator(*key))
+            }
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut changed = false;
+        let mut x = 0.0;
+        for title in &mut self.titles {
+            let (size, c) = title.text.size();
+            changed |= c;
+            title.size = size.get_min();
+            let width = title.size.width + Self::TITLE_PADDING * 2.0;
+            title.rect = Rect::from_xywh(x, 0.0, width, Self::HEIGHT);
+            x += width;
+        }
+        (LayoutSize::min(x, Self::HEIGHT).expand_width(), changed)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.size = size;
+        for title in &mut self.titles {
+            title.text.set_size(title.size);
+        }
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        let style = &self.style;
+        canvas.draw_rect(Rect::from_size(self.size), &style.background);
+        let active = self.open.or(self.hovered);
+        for (i, title) in self.titles.iter_mut().enumerate() {
+            if active == Some(i) {
+                canvas.draw_rect(title.rect, &style.highlight);
+            }
+            let x = title.rect.left + Self::TITLE_PADDING;
+            let mnemonic = title.mnemonic.map(|m| m.0);
+            draw_menu_text(
+                canvas,
+                &mut title.text,
+                title.size,
+                title.rect,
+                x,
+                mnemonic,
+                &style.text,
+            );
+        }
+    }
+
+    fn hover_lost(&mut self, _state: &mut WidgetState) {
+        self.hovered = None;
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for title in &self.titles {
+            f(&title.text);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("open", format!("{:?}", self.open))]
+    }
+}
+
+/// Opens a menu at the pointer when its child is right-clicked.
+///
+/// The menu's accelerators work whenever the child gets key presses.
+pub struct ContextMenu<T: Widget + ?Sized> {
+    child: Wrap<T>,
+    menu: Menu,
+    style: MenuStyle,
+    resources: Option<ResourceStack>,
+    handle: Option<OverlayHandle>,
+}
+
+impl<T: Widget + ?Sized> ContextMenu<T> {
+    pub fn new(child: Wrap<T>, menu: Menu, style: MenuStyle) -> Wrap<Self> {
+        FrameworkState::request_load();
+        Self {
+            child,
+            menu,
+            style,
+            resources: None,
+            handle: None,
+        }
+        .into()
+    }
+
+    pub fn menu(&self) -> &Menu {
+        &self.menu
+    }
+
+    /// The menu, for changes that show the next time it opens.
+    pub fn menu_mut(&mut self) -> &mut Menu {
+        &mut self.menu
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.handle.as_ref().map_or(false, OverlayHandle::is_open)
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for ContextMenu<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.resources = Some(stack.snapshot());
+        self.child.load(stack);
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        self.child.update();
+    }
+
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        if self.child.input(event) {
+            return true;
+        }
+        let event = unfocus_key(event);
+        match event {
+            InputEvent::MouseDown(MouseButton::Right, p)
+                if Rect::from_size(state.current_size()).contains(*p) =>
+            {
+                let anchor = Anchor::Point(state.matrix().map_point(*p));
+                let resources = self.resources.as_ref().map(|r| r.snapshot());
+                let handle = self.menu.popup(anchor, &self.style, resources);
+                self.handle = Some(handle);
+                true
+            }
+            InputEvent::KeyDown(key) => self.menu.trigger_accelerator(*key),
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        self.child.size()
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.child.set_size(size);
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        self.child.draw(canvas);
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.child);
+    }
+}
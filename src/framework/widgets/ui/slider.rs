@@ -278,4 +278,14 @@ impl Widget for Slider {
         canvas.restore();
         self.label.draw(canvas);
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.background);
+        f(&self.button);
+        f(&self.label);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("value", self.value.to_string())]
+    }
 }
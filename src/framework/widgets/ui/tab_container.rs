@@ -0,0 +1,626 @@
+use super::super::layout::ContainerSize;
+use super::super::{FontName, FontStyle, Text, TextLayoutMode, TreeNode};
+use super::menu::Accelerator;
+use crate::game::ID;
+use crate::prelude::*;
+use skia::Point;
+
+type PageBuilder<T> = dyn FnOnce() -> Wrap<T>;
+
+/// How a [TabContainer] draws its row of tabs.
+#[derive(Clone)]
+pub struct TabStyle {
+    /// Behind the whole row.
+    pub background: Paint,
+    /// Behind the selected tab.
+    pub active: Paint,
+    /// Behind the tab under the pointer.
+    pub hover: Paint,
+    pub text: Paint,
+    pub font: Option<FontName>,
+    pub font_size: Option<scalar>,
+}
+
+impl TabStyle {
+    /// A style with the hovered tab drawn as a faded `active`.
+    pub fn new(background: Paint, active: Paint, text: Paint) -> Self {
+        let mut hover = active.clone();
+        hover.set_alpha_f(active.alpha_f() * 0.5);
+        Self {
+            background,
+            active,
+            hover,
+            text,
+            font: None,
+            font_size: None,
+        }
+    }
+}
+
+struct Tab<T: Widget + ?Sized> {
+    title: Wrap<Text>,
+    title_size: Size,
+    icon: Option<Wrap<dyn Widget>>,
+    icon_size: Size,
+    /// `None` until a lazily built page is first selected.
+    page: Option<Wrap<T>>,
+    build: Option<Box<PageBuilder<T>>>,
+    closable: bool,
+    rect: Rect,
+    close_rect: Option<Rect>,
+}
+
+/// A tab being pressed, and maybe dragged along the row.
+struct Press {
+    index: usize,
+    start: scalar,
+    dragging: bool,
+}
+
+/// A row of tabs above one page, showing the page of whichever tab is
+/// selected.
+///
+/// Only the selected page is updated, sized, given input and drawn. Pages
+/// added with [TabContainer::add_lazy_tab] aren't even built until they're
+/// first selected.
+///
+/// Tabs can be selected by clicking them or with Ctrl+Tab and
+/// Ctrl+Shift+Tab, and dragged along the row to reorder them. Closable tabs
+/// get a button to close them.
+pub struct TabContainer<T: Widget + ?Sized> {
+    /// Not the ID of any widget, for taking hover away from everything in a
+    /// page being hidden.
+    id: ID,
+    style: TabStyle,
+    tabs: Vec<Tab<T>>,
+    selected: Option<usize>,
+    hovered: Option<usize>,
+    press: Option<Press>,
+    on_change_fns: Vec<Box<dyn FnMut(usize)>>,
+    on_close_fns: Vec<Box<dyn FnMut(usize)>>,
+    resources: Option<ResourceStack>,
+    size: ContainerSize,
+    page_layout_size: LayoutSize,
+    current_size: Size,
+    needs_layout: bool,
+}
+
+pub type TabContainerDyn = TabContainer<dyn Widget>;
+
+impl<T: Widget + ?Sized> TabContainer<T> {
+    const HEIGHT: scalar = 30.0;
+    const PADDING: scalar = 12.0;
+    const ICON_GAP: scalar = 6.0;
+    const CLOSE_SIZE: scalar = 14.0;
+    const CLOSE_GAP: scalar = 8.0;
+    const DRAG_THRESHOLD: scalar = 6.0;
+
+    pub fn new(style: TabStyle, size: ContainerSize) -> Wrap<Self> {
+        // `FrameworkState::request_load();` here is not needed, as there are
+        // no children just yet.
+        Self {
+            id: ID::next(),
+            style,
+            tabs: vec![],
+            selected: None,
+            hovered: None,
+            press: None,
+            on_change_fns: vec![],
+            on_close_fns: vec![],
+            resources: None,
+            size,
+            page_layout_size: LayoutSize::ZERO,
+            current_size: Size::default(),
+            needs_layout: false,
+        }
+        .into()
+    }
+
+    /// Adds a tab at the end of the row. The first tab added is selected.
+    pub fn add_tab(&mut self, title: &str, page: Wrap<T>) -> &mut Self {
+        self.push_tab(title, Some(page), None)
+    }
+
+    /// Adds a tab whose page is built by `build` when it's first selected,
+    /// for pages that are expensive to build or load.
+    pub fn add_lazy_tab<F>(&mut self, title: &str, build: F) -> &mut Self
+    where
+        F: FnOnce() -> Wrap<T> + 'static,
+    {
+        self.push_tab(title, None, Some(Box::new(build)))
+    }
+
+    /// Removes the tab at `index`, returning its page if it was ever built.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_tab(&mut self, index: usize) -> Option<Wrap<T>> {
+        if self.selected == Some(index) {
+            self.deactivate(index);
+        }
+        let tab = self.tabs.remove(index);
+        self.hovered = None;
+        self.press = None;
+        let selected = self.selected;
+        self.selected = match selected {
+            Some(s) if s > index => Some(s - 1),
+            Some(s) if s == index && !self.tabs.is_empty() => {
+                let s = s.min(self.tabs.len() - 1);
+                self.activate(s);
+                Some(s)
+            }
+            Some(s) if s == index => None,
+            s => s,
+        };
+        self.layout_headers();
+        self.needs_layout = true;
+        if selected == Some(index) {
+            if let Some(s) = self.selected {
+                self.changed(s);
+            }
+        }
+        tab.page
+    }
+
+    /// Moves the tab at `from` to `to`, shifting the ones between along.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        let moved = |i: usize| {
+            if i == from {
+                to
+            } else if from < i && i <= to {
+                i - 1
+            } else if to <= i && i < from {
+                i + 1
+            } else {
+                i
+            }
+        };
+        self.selected = self.selected.map(moved);
+        self.hovered = self.hovered.map(moved);
+        self.layout_headers();
+    }
+
+    /// Selects the tab at `index`, building its page if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn select(&mut self, index: usize) {
+        assert!(index < self.tabs.len(), "tab index out of bounds");
+        if self.selected != Some(index) {
+            if let Some(old) = self.selected {
+                self.deactivate(old);
+            }
+            self.selected = Some(index);
+            self.activate(index);
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// The page of the tab at `index`, unless it's yet to be built.
+    pub fn page(&self, index: usize) -> Option<&Wrap<T>> {
+        self.tabs.get(index).and_then(|tab| tab.page.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Changes the title of the tab at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_title(&mut self, index: usize, title: &str) {
+        self.tabs[index]
+            .title
+            .inner_mut()
+            .set_text(title.to_owned());
+        self.needs_layout = true;
+    }
+
+    /// Shows `icon` before the title of the tab at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_icon(&mut self, index: usize, icon: Option<Wrap<dyn Widget>>) {
+        FrameworkState::request_load();
+        self.tabs[index].icon = icon;
+        self.needs_layout = true;
+    }
+
+    /// Gives the tab at `index` a button to close it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_closable(&mut self, index: usize, closable: bool) {
+        self.tabs[index].closable = closable;
+        self.layout_headers();
+    }
+
+    /// Adds a callback, called with the index of the newly selected tab
+    /// whenever the user switches tabs, and whenever another tab is selected
+    /// in place of a removed one.
+    pub fn on_change<F: FnMut(usize) + 'static>(&mut self, f: F) {
+        self.on_change_fns.push(Box::new(f));
+    }
+
+    /// Adds a callback, called with the index a tab had whenever the user
+    /// closes it.
+    pub fn on_close<F: FnMut(usize) + 'static>(&mut self, f: F) {
+        self.on_close_fns.push(Box::new(f));
+    }
+
+    fn push_tab(
+        &mut self,
+        title: &str,
+        page: Option<Wrap<T>>,
+        build: Option<Box<PageBuilder<T>>>,
+    ) -> &mut Self {
+        FrameworkState::request_load();
+        let title = Text::new(
+            LayoutSize::ZERO,
+            Some(TextLayoutMode::OneLine),
+            title,
+            self.style.font.clone(),
+            FontStyle::Regular,
+            self.style.font_size,
+            self.style.text.clone(),
+        );
+        self.tabs.push(Tab {
+            title,
+            title_size: Size::default(),
+            icon: None,
+            icon_size: Size::default(),
+            page,
+            build,
+            closable: false,
+            rect: Rect::default(),
+            close_rect: None,
+        });
+        if self.selected.is_none() {
+            self.selected = Some(0);
+            self.activate(0);
+        }
+        self
+    }
+
+    /// Gets the page at `index` ready to be shown.
+    fn activate(&mut self, index: usize) {
+        let tab = &mut self.tabs[index];
+        if let Some(build) = tab.build.take() {
+            let mut page = build();
+            match &mut self.resources {
+                Some(stack) => page.load(stack),
+                None => FrameworkState::request_load(),
+            }
+            tab.page = Some(page);
+        }
+        // The page hasn't been sized while it was hidden.
+        self.page_layout_size = LayoutSize::ZERO;
+        self.needs_layout = true;
+    }
+
+    /// Takes hover and focus away from anything in the page at `index`, as
+    /// it's about to be hidden.
+    fn deactivate(&mut self, index: usize) {
+        let id = self.id;
+        if let Some(page) = &mut self.tabs[index].page {
+            page.input(&InputEvent::RemoveHoverExcept(id));
+            if let Some(focus) = FrameworkState::current_focus() {
+                if TreeNode::find_info(&*page, focus).is_some() {
+                    FrameworkState::release_focus(focus);
+                }
+            }
+        }
+    }
+
+    fn changed(&mut self, index: usize) {
+        for f in &mut self.on_change_fns {
+            f(index);
+        }
+    }
+
+    /// Selects a tab as asked for by the user.
+    fn switch_to(&mut self, index: usize) {
+        if self.selected != Some(index) {
+            self.select(index);
+            self.changed(index);
+        }
+    }
+
+    fn close_tab(&mut self, index: usize) {
+        self.remove_tab(index);
+        for f in &mut self.on_close_fns {
+            f(index);
+        }
+    }
+
+    /// Switches `step` tabs along, wrapping around.
+    fn step(&mut self, step: isize) {
+        let len = self.tabs.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let current = self.selected.map_or(0, |s| s as isize);
+        self.switch_to((current + step).rem_euclid(len) as usize);
+    }
+
+    /// Switches tabs for Ctrl+Tab and Ctrl+Shift+Tab, returning whether
+    /// `key` was either.
+    fn step_key(&mut self, key: Keycode) -> bool {
+        let next = Accelerator::ctrl(Keycode::Tab);
+        if next.matches(key) {
+            self.step(1);
+            true
+        } else if next.with_shift().matches(key) {
+            self.step(-1);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn tab_at(&self, p: Point) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.rect.contains(p))
+    }
+
+    /// Places tabs along the row, going by their sizes as of the last size
+    /// pass.
+    fn layout_headers(&mut self) {
+        let mut x = 0.0;
+        for tab in &mut self.tabs {
+            let mut width = Self::PADDING * 2.0 + tab.title_size.width;
+            if tab.icon.is_some() {
+                width += tab.icon_size.width + Self::ICON_GAP;
+            }
+            tab.close_rect = if tab.closable {
+                width += Self::CLOSE_GAP + Self::CLOSE_SIZE;
+                let left = x + width - Self::PADDING - Self::CLOSE_SIZE;
+                let top = (Self::HEIGHT - Self::CLOSE_SIZE) * 0.5;
+                Some(Rect::from_xywh(
+                    left,
+                    top,
+                    Self::CLOSE_SIZE,
+                    Self::CLOSE_SIZE,
+                ))
+            } else {
+                None
+            };
+            tab.rect = Rect::from_xywh(x, 0.0, width, Self::HEIGHT);
+            x += width;
+        }
+    }
+
+    fn press_tab(&mut self, state: &mut WidgetState, p: Point) -> bool {
+        let index = match self.tab_at(p) {
+            Some(index) => index,
+            None => return false,
+        };
+        if self.tabs[index].close_rect.map_or(false, |r| r.contains(p)) {
+            self.close_tab(index);
+            return true;
+        }
+        self.switch_to(index);
+        self.press = Some(Press {
+            index,
+            start: p.x,
+            dragging: false,
+        });
+        state.grab_focus();
+        true
+    }
+
+    /// Moves a pressed tab along the row, past any neighbour whose middle
+    /// the pointer crosses.
+    fn drag_tab(&mut self, p: Point) {
+        let press = match &mut self.press {
+            Some(press) => press,
+            None => return,
+        };
+        press.dragging |= (p.x - press.start).abs() > Self::DRAG_THRESHOLD;
+        if !press.dragging {
+            return;
+        }
+        let i = press.index;
+        let to = if i > 0 && p.x < self.tabs[i - 1].rect.center_x() {
+            i - 1
+        } else if i + 1 < self.tabs.len()
+            && p.x > self.tabs[i + 1].rect.center_x()
+        {
+            i + 1
+        } else {
+            return;
+        };
+        press.index = to;
+        self.move_tab(i, to);
+    }
+
+    fn page_mut(&mut self) -> Option<&mut Wrap<T>> {
+        let selected = self.selected?;
+        self.tabs[selected].page.as_mut()
+    }
+
+    fn page_size(&self) -> Size {
+        let size = self.current_size;
+        Size::new(size.width, (size.height - Self::HEIGHT).max(0.0))
+    }
+}
+
+impl<T: Widget + ?Sized> Widget for TabContainer<T> {
+    fn load(&mut self, _state: &mut WidgetState, stack: &mut ResourceStack) {
+        self.resources = Some(stack.snapshot());
+        for tab in &mut self.tabs {
+            tab.title.load(stack);
+            if let Some(icon) = &mut tab.icon {
+                icon.load(stack);
+            }
+            if let Some(page) = &mut tab.page {
+                page.load(stack);
+            }
+        }
+    }
+
+    fn update(&mut self, _state: &mut WidgetState) {
+        if let Some(page) = self.page_mut() {
+            page.update();
+        }
+    }
+
+    fn input(&mut self, state: &mut WidgetState, event: &InputEvent) -> bool {
+        let m = Matrix::translate((0.0, Self::HEIGHT));
+        if let (Some(event), Some(page)) =
+            (event.reverse_map_position(m), self.page_mut())
+        {
+            if page.input(&event) && event.is_consumable() {
+                return true;
+            }
+        }
+
+        match event {
+            InputEvent::MouseMove(p) => {
+                self.hovered = self.tab_at(*p);
+                self.drag_tab(*p);
+                self.hovered.is_some()
+            }
+            InputEvent::MouseDown(MouseButton::Left, p) => {
+                self.press_tab(state, *p)
+            }
+            InputEvent::MouseUp(MouseButton::Left, _)
+                if self.press.is_some() =>
+            {
+                self.press = None;
+                state.release_focus();
+                true
+            }
+            InputEvent::KeyDown(key) => self.step_key(*key),
+            // Switching tabs works even while something else has focus.
+            InputEvent::Focused(_, inner) => match inner.as_ref() {
+                InputEvent::KeyDown(key) => self.step_key(*key),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn size(&mut self, _state: &mut WidgetState) -> (LayoutSize, bool) {
+        let mut changed = std::mem::take(&mut self.needs_layout);
+        for tab in &mut self.tabs {
+            let (size, c) = tab.title.size();
+            changed |= c || tab.title_size != size.get_min();
+            tab.title_size = size.get_min();
+            if let Some(icon) = &mut tab.icon {
+                let (size, c) = icon.size();
+                changed |= c || tab.icon_size != size.get_min();
+                tab.icon_size = size.get_min();
+            }
+        }
+        self.layout_headers();
+        let row_width = self.tabs.last().map_or(0.0, |tab| tab.rect.right);
+
+        if let Some(page) = self.page_mut() {
+            let (size, c) = page.size();
+            changed |= c || self.page_layout_size != size;
+            self.page_layout_size = size;
+        }
+        let page = self.page_layout_size;
+        let min = LayoutSize::min(
+            row_width.max(page.width.min),
+            Self::HEIGHT + page.height.min,
+        );
+        (self.size.apply(&min), changed)
+    }
+
+    fn set_size(&mut self, _state: &mut WidgetState, size: Size) {
+        self.current_size = size;
+        for tab in &mut self.tabs {
+            tab.title.set_size(tab.title_size);
+            if let Some(icon) = &mut tab.icon {
+                icon.set_size(tab.icon_size);
+            }
+        }
+        let page_size = self.page_layout_size.layout_one(self.page_size());
+        if let Some(page) = self.page_mut() {
+            page.set_size(page_size);
+        }
+    }
+
+    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+        let style = &self.style;
+        let row = Rect::from_wh(self.current_size.width, Self::HEIGHT);
+        canvas.draw_rect(row, &style.background);
+        let mut stroke = style.text.clone();
+        stroke.set_stroke_width(1.5);
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            if self.selected == Some(i) {
+                canvas.draw_rect(tab.rect, &style.active);
+            } else if self.hovered == Some(i) {
+                canvas.draw_rect(tab.rect, &style.hover);
+            }
+            let mut x = tab.rect.left + Self::PADDING;
+            if let Some(icon) = &mut tab.icon {
+                let y = (Self::HEIGHT - tab.icon_size.height) * 0.5;
+                canvas.save();
+                canvas.translate((x, y));
+                icon.draw(canvas);
+                canvas.restore();
+                x += tab.icon_size.width + Self::ICON_GAP;
+            }
+            let y = (Self::HEIGHT - tab.title_size.height) * 0.5;
+            canvas.save();
+            canvas.translate((x, y));
+            tab.title.draw(canvas);
+            canvas.restore();
+            if let Some(r) = tab.close_rect {
+                let r = r.with_inset((3.0, 3.0));
+                canvas.draw_line((r.left, r.top), (r.right, r.bottom), &stroke);
+                canvas.draw_line((r.left, r.bottom), (r.right, r.top), &stroke);
+            }
+        }
+
+        let page_size = self.page_size();
+        if let Some(page) = self.page_mut() {
+            canvas.save();
+            canvas.translate((0.0, Self::HEIGHT));
+            canvas.clip_rect(Rect::from_size(page_size), None, true);
+            page.draw(canvas);
+            canvas.restore();
+        }
+    }
+
+    fn hover_lost(&mut self, _state: &mut WidgetState) {
+        self.hovered = None;
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        for tab in &self.tabs {
+            if let Some(icon) = &tab.icon {
+                f(icon);
+            }
+            f(&tab.title);
+        }
+        if let Some(page) = self.selected.and_then(|s| self.page(s)) {
+            f(page);
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("selected", format!("{:?}", self.selected))]
+    }
+}
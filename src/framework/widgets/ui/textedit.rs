@@ -316,10 +316,10 @@ impl Widget for TextEdit {
         self.invalidate_cursor();
     }
 
-    fn draw(&mut self, _state: &mut WidgetState, canvas: &mut Canvas) {
+    fn draw(&mut self, state: &mut WidgetState, canvas: &mut Canvas) {
         self.text.draw(canvas);
         if self.take_input {
-            let t = State::elapsed_draw().as_secs_f32();
+            let t = state.elapsed().as_secs_f32();
             self.cursor_paint.set_alpha_f((t * 8.0).sin() * 0.5 + 0.5);
             if let Some(pos) = self.update_cursor() {
                 canvas.draw_rect(
@@ -329,6 +329,14 @@ impl Widget for TextEdit {
             }
         }
     }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        f(&self.text);
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![("cursor", self.cursor.byte_offset.to_string())]
+    }
 }
 
 struct Cursor {
@@ -1,14 +1,26 @@
-use super::{LayoutSize, Widget};
+use super::animation::Playback;
+use super::layout::Screen;
+use super::timeline::TimelinePlayback;
+use super::{
+    Clock, DrawProps, LayoutSize, Sequence, Timeline, Widget, WidgetInfo,
+    WidgetNode,
+};
 
 use std::cell::{Ref, RefCell, RefMut};
 use std::mem::transmute;
 use std::ops::{Deref, DerefMut};
 use std::rc::{Rc, Weak};
+use std::time::Duration;
 
-use crate::game::{InputEvent, ID};
-use crate::skia::{Canvas, Size};
+use crate::game::{InputEvent, State, ID};
+use crate::skia::canvas::SaveLayerRec;
+use crate::skia::{
+    color_filters, scalar, BlendMode, Canvas, Color4f, Matrix, Paint, Point,
+    Rect, Size,
+};
 
-use super::super::resource::ResourceStack;
+use super::super::profiler::{Pass, Profiler};
+use super::super::resource::{ResourceStack, ResourceUser};
 use super::super::FrameworkState;
 
 pub struct WidgetBorrow<'a, T: 'a + Widget + ?Sized> {
@@ -73,6 +85,12 @@ impl<'a, T: Widget + 'a> From<Wrap<T>> for Wrap<dyn Widget + 'a> {
     }
 }
 
+impl<'a, T: Screen + 'a> From<Wrap<T>> for Wrap<dyn Screen + 'a> {
+    fn from(wrap: Wrap<T>) -> Self {
+        Wrap { inner: wrap.inner }
+    }
+}
+
 impl<'a, T: 'a + Widget + ?Sized> Wrap<T> {
     pub fn id(&self) -> ID {
         self.inner.borrow().state.id()
@@ -143,6 +161,56 @@ impl<'a, T: 'a + Widget + ?Sized> Wrap<T> {
         let inner = &mut s.inner;
         state.draw(inner, canvas);
     }
+
+    /// See [Widget::baseline].
+    pub fn baseline(&self) -> Option<scalar> {
+        let s = self.inner.borrow();
+        s.inner.baseline(&s.state)
+    }
+
+    /// Plays an animation on this widget's [DrawProps].
+    pub fn animate(&self, sequence: Sequence) {
+        self.inner.borrow_mut().state.animate(sequence);
+    }
+
+    /// Plays a timeline on this widget's [DrawProps].
+    pub fn play_timeline(&self, timeline: Rc<Timeline>) {
+        self.inner.borrow_mut().state.play_timeline(timeline);
+    }
+
+    /// Stops playing a timeline, see [WidgetState::stop_timeline].
+    pub fn stop_timeline(&self, timeline: &Rc<Timeline>) {
+        self.inner.borrow_mut().state.stop_timeline(timeline);
+    }
+
+    pub fn props(&self) -> DrawProps {
+        *self.inner.borrow().state.props()
+    }
+
+    pub fn props_mut(&self) -> RefMut<'_, DrawProps> {
+        RefMut::map(self.inner.borrow_mut(), |s| s.state.props_mut())
+    }
+}
+
+impl<T: Widget + ?Sized> WidgetNode for Wrap<T> {
+    fn info(&self) -> WidgetInfo {
+        let s = self.inner.borrow();
+        WidgetInfo {
+            id: s.state.id(),
+            type_name: s.inner.type_name(),
+            layout_size: s.state.layout_size(),
+            size: s.state.current_size(),
+            position: s.state.position(),
+            bounds: s.state.screen_rect(),
+            is_hovered: s.state.is_hovered(),
+            is_focused: s.state.is_focused(),
+            properties: s.inner.properties(),
+        }
+    }
+
+    fn visit_children(&self, f: &mut dyn FnMut(&dyn WidgetNode)) {
+        self.inner.borrow().inner.visit_children(f);
+    }
 }
 
 impl<T: Widget + ?Sized> Clone for Wrap<T> {
@@ -205,6 +273,14 @@ pub struct WidgetState {
     id: ID,
     is_hovered: bool,
     was_hovered: bool,
+    layout_size: LayoutSize,
+    size: Size,
+    matrix: Matrix,
+    clock: ResourceUser<Clock>,
+    props: DrawProps,
+    applied_size: Option<Size>,
+    playbacks: Vec<Playback>,
+    timelines: Vec<TimelinePlayback>,
 }
 
 impl WidgetState {
@@ -213,6 +289,14 @@ impl WidgetState {
             id: ID::next(),
             is_hovered: false,
             was_hovered: false,
+            layout_size: LayoutSize::ZERO,
+            size: Size::new_empty(),
+            matrix: Matrix::default(),
+            clock: ResourceUser::new_none(),
+            props: DrawProps::default(),
+            applied_size: None,
+            playbacks: vec![],
+            timelines: vec![],
         }
     }
 
@@ -224,15 +308,161 @@ impl WidgetState {
         self.is_hovered
     }
 
+    /// The layout size the widget reported the last time it was asked.
+    pub fn layout_size(&self) -> LayoutSize {
+        self.layout_size
+    }
+
+    /// The size the widget was last given by its parent.
+    pub fn current_size(&self) -> Size {
+        self.size
+    }
+
+    /// The transformation from this widget's coordinates to window
+    /// coordinates, in logical pixels, as of the last time it was drawn.
+    pub fn matrix(&self) -> Matrix {
+        self.matrix
+    }
+
+    /// The position of this widget's origin in window coordinates, as of the
+    /// last time it was drawn.
+    pub fn position(&self) -> Point {
+        self.matrix.map_point(Point::default())
+    }
+
+    /// The bounds of this widget in window coordinates, as of the last time
+    /// it was drawn.
+    pub fn screen_rect(&self) -> Rect {
+        self.matrix.map_rect(Rect::from_size(self.size)).0
+    }
+
+    /// Time elapsed on the nearest [Clock], or since the first draw if there
+    /// is none.
+    pub fn elapsed(&self) -> Duration {
+        match self.clock.try_access() {
+            Some(clock) => clock.elapsed(),
+            None => State::elapsed_draw(),
+        }
+    }
+
+    /// Time elapsed on the nearest [Clock] during the last draw, or the
+    /// time since the last draw if there is none.
+    pub fn delta(&self) -> Duration {
+        match self.clock.try_access() {
+            Some(clock) => clock.delta(),
+            None => State::last_update_time_draw(),
+        }
+    }
+
+    /// The nearest [Clock] above this widget.
+    pub fn clock(&self) -> &ResourceUser<Clock> {
+        &self.clock
+    }
+
+    /// Properties applied on top of whatever the widget draws.
+    pub fn props(&self) -> &DrawProps {
+        &self.props
+    }
+
+    pub fn props_mut(&mut self) -> &mut DrawProps {
+        &mut self.props
+    }
+
+    /// Plays an animation on this widget's [DrawProps], along with any
+    /// already playing. Animations follow the widget's clock, and advance
+    /// whenever the widget is drawn.
+    pub fn animate(&mut self, sequence: Sequence) {
+        self.playbacks.push(Playback::new(sequence));
+    }
+
+    /// Plays a timeline on this widget's [DrawProps], starting now on the
+    /// widget's clock.
+    ///
+    /// Seeking the clock scrubs through the timeline. Once a timeline that
+    /// doesn't loop is done, its last values are applied and it leaves the
+    /// properties to other animations, until the clock is sought back into
+    /// it. Timelines are kept until [WidgetState::stop_timeline] or
+    /// [WidgetState::stop_animations].
+    pub fn play_timeline(&mut self, timeline: Rc<Timeline>) {
+        self.timelines.push(TimelinePlayback::new(timeline, None));
+    }
+
+    /// Like [WidgetState::play_timeline], but starting at `start` on the
+    /// widget's clock. With a start of zero, the timeline's position is the
+    /// clock's.
+    pub fn play_timeline_from(
+        &mut self,
+        timeline: Rc<Timeline>,
+        start: Duration,
+    ) {
+        self.timelines
+            .push(TimelinePlayback::new(timeline, Some(start)));
+    }
+
+    /// Stops every playback of `timeline`, leaving properties as they are.
+    pub fn stop_timeline(&mut self, timeline: &Rc<Timeline>) {
+        self.timelines
+            .retain(|t| !Rc::ptr_eq(t.timeline(), timeline));
+    }
+
+    /// Stops every animation and timeline, leaving properties as they are.
+    pub fn stop_animations(&mut self) {
+        self.playbacks.clear();
+        self.timelines.clear();
+    }
+
+    pub fn is_animating(&self) -> bool {
+        let now = self.elapsed();
+        !self.playbacks.is_empty()
+            || self.timelines.iter().any(|t| !t.is_done(now))
+    }
+
+    fn advance_animations(&mut self) {
+        if self.playbacks.is_empty() && self.timelines.is_empty() {
+            return;
+        }
+        let now = self.elapsed();
+        for timeline in &mut self.timelines {
+            timeline.apply(now, &mut self.props);
+        }
+        let mut i = 0;
+        while i < self.playbacks.len() {
+            if self.playbacks[i].apply(now, &mut self.props, self.size) {
+                self.playbacks.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn tint_paint(&self) -> Paint {
+        let c = self.props.colour;
+        let mut paint = Paint::default();
+        paint.set_alpha_f(self.props.alpha * c.a);
+        if (c.r, c.g, c.b) != (1.0, 1.0, 1.0) {
+            let colour = Color4f::new(c.r, c.g, c.b, 1.0).to_color();
+            paint.set_color_filter(color_filters::blend(
+                colour,
+                BlendMode::Modulate,
+            ));
+        }
+        paint
+    }
+
     pub fn load<T: Widget + ?Sized>(
         &mut self,
         widget: &mut T,
         stack: &mut ResourceStack,
     ) {
+        self.clock = stack
+            .get::<ResourceUser<Clock>>()
+            .cloned()
+            .unwrap_or_else(ResourceUser::new_none);
         widget.load(self, stack);
     }
 
     pub fn update<T: Widget + ?Sized>(&mut self, widget: &mut T) {
+        let _span = Profiler::span(self.id, widget.type_name(), Pass::Update);
         widget.update(self);
     }
 
@@ -241,6 +471,28 @@ impl WidgetState {
         widget: &mut T,
         event: &InputEvent,
     ) -> bool {
+        let _span = Profiler::span(self.id, widget.type_name(), Pass::Input);
+        let local;
+        let event = if self.props.is_transformed() {
+            let m = self.props.matrix(self.size);
+            match event.reverse_map_position(m) {
+                Some(e) => {
+                    local = e;
+                    &local
+                }
+                // Scaled down to nothing, so the pointer can't be over the
+                // widget, but events without a position still get through.
+                None if event.position().is_some() => {
+                    if let InputEvent::MouseMove(_) = event {
+                        self.is_hovered = false;
+                    }
+                    return false;
+                }
+                None => event,
+            }
+        } else {
+            event
+        };
         match event {
             InputEvent::RemoveHoverExcept(id) => {
                 let b = self.id == *id;
@@ -271,10 +523,21 @@ impl WidgetState {
         &mut self,
         widget: &mut T,
     ) -> (LayoutSize, bool) {
-        widget.size(self)
+        let _span = Profiler::span(self.id, widget.type_name(), Pass::Size);
+        let (mut layout_size, mut changed) = widget.size(self);
+        if let Some(size) = self.props.size {
+            layout_size.width.min = size.width;
+            layout_size.height.min = size.height;
+        }
+        changed |= self.props.size != self.applied_size;
+        self.applied_size = self.props.size;
+        self.layout_size = layout_size;
+        (layout_size, changed)
     }
 
     pub fn set_size<T: Widget + ?Sized>(&mut self, widget: &mut T, size: Size) {
+        let _span = Profiler::span(self.id, widget.type_name(), Pass::SetSize);
+        self.size = size;
         widget.set_size(self, size);
     }
 
@@ -283,6 +546,20 @@ impl WidgetState {
         widget: &mut T,
         canvas: &mut Canvas,
     ) {
+        let _span = Profiler::span(self.id, widget.type_name(), Pass::Draw);
+        self.advance_animations();
+        let count = canvas.save();
+        if self.props.is_transformed() {
+            canvas.concat(&self.props.matrix(self.size));
+        }
+        if self.props.is_tinted() {
+            let paint = self.tint_paint();
+            canvas.save_layer(&SaveLayerRec::default().paint(&paint));
+        }
+
+        let sf = State::scale_factor() as scalar;
+        self.matrix = Matrix::scale((1.0 / sf, 1.0 / sf));
+        self.matrix.pre_concat(&canvas.total_matrix());
         if self.is_hovered != self.was_hovered {
             self.was_hovered = self.is_hovered;
             if self.is_hovered {
@@ -292,6 +569,7 @@ impl WidgetState {
             }
         }
         widget.draw(self, canvas);
+        canvas.restore_to_count(count);
     }
 
     pub fn grab_focus(&self) {
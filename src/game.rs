@@ -6,7 +6,7 @@ mod time;
 pub use builder::Builder;
 pub use input::{EventHandleResult, InputEvent, InputState, ScrollAmount};
 pub use runner::{GameError, RunnerError, State, ID};
-pub use time::TimeState;
+pub use time::{FrameStats, TimeState};
 
 pub trait Game {
     fn update(&mut self);
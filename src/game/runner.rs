@@ -26,7 +26,7 @@ use gl::types::GLint;
 use gl_rs as gl;
 
 use super::input::{EventHandleResult, InputState};
-use super::time::TimeState;
+use super::time::{FrameStats, TimeState};
 use super::Game;
 
 type WindowedContext = GlutinContextWrapper<GlutinPossiblyCurrent, Window>;
@@ -105,6 +105,17 @@ impl ID {
             id
         }))
     }
+
+    /// The raw value of this ID, mostly useful for debugging and tooling.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for ID {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "#{}", self.0)
+    }
 }
 
 pub struct State {
@@ -163,7 +174,7 @@ impl State {
         })
     }
 
-    fn scale_factor() -> f64 {
+    pub(crate) fn scale_factor() -> f64 {
         Self::with(|x| x.input_state.scale_factor())
     }
 }
@@ -186,6 +197,34 @@ impl State {
         Self::with(|x| x.time_state_draw.elapsed())
     }
 
+    /// Statistics over recent update frame times.
+    pub fn update_stats() -> FrameStats {
+        Self::with(|x| x.time_state.stats().clone())
+    }
+
+    /// Statistics over recent draw frame times.
+    pub fn draw_stats() -> FrameStats {
+        Self::with(|x| x.time_state_draw.stats().clone())
+    }
+
+    pub fn target_update_time() -> Duration {
+        Self::with(|x| x.time_state.stats().target())
+    }
+
+    /// Sets how often updates should happen. Defaults to every 2ms.
+    pub fn set_target_update_time(target: Duration) {
+        Self::with_mut(|x| x.time_state.stats_mut().set_target(target));
+    }
+
+    pub fn target_draw_time() -> Duration {
+        Self::with(|x| x.time_state_draw.stats().target())
+    }
+
+    /// Sets how often draws should happen. Defaults to every 5ms.
+    pub fn set_target_draw_time(target: Duration) {
+        Self::with_mut(|x| x.time_state_draw.stats_mut().set_target(target));
+    }
+
     pub fn is_fullscreen() -> bool {
         Self::with(|x| x.is_fullscreen)
     }
@@ -239,12 +278,6 @@ where
     };
     game.set_size(State::with(|x| x.input_state.window_size));
 
-    let target_update_time = Duration::from_millis(2); // 500 fps
-
-    // Most displays are <= 200 fps.
-    // Of course this will be upgraded from a hard-coded value in the future.
-    let target_draw_time = Duration::from_millis(5); // 200 fps
-
     event_loop.run(move |event, _, flow| match event {
         Event::WindowEvent { event, .. } => {
             if let WindowEvent::Resized(size) = &event {
@@ -270,6 +303,7 @@ where
             }
 
             State::with_mut(|state| {
+                let target_draw_time = state.time_state_draw.stats().target();
                 let last_draw = state.time_state_draw.last_update();
                 let last_draw_time = last_draw.elapsed();
                 if last_draw_time >= target_draw_time {
@@ -293,6 +327,7 @@ where
         }
         Event::RedrawEventsCleared => {
             State::with_mut(|state| {
+                let target_update_time = state.time_state.stats().target();
                 let last_update = state.time_state.last_update();
                 let last_update_time = last_update.elapsed();
                 if last_update_time < target_update_time {
@@ -416,8 +451,9 @@ fn init_runner(
 
 fn init_state(win: &Window) {
     let input_state = InputState::new(win);
-    let time_state = TimeState::new();
-    let time_state_draw = TimeState::new();
+    // Update at 500 fps. Most displays are <= 200 fps, so draw at that.
+    let time_state = TimeState::with_target(Duration::from_millis(2));
+    let time_state_draw = TimeState::with_target(Duration::from_millis(5));
     State::STATE.with(|x| {
         *x.borrow_mut() = Some(State {
             input_state,
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
 pub struct TimeState {
@@ -5,16 +6,23 @@ pub struct TimeState {
     last_update: Instant,
     elapsed: Duration,
     last_update_time: Duration,
+    stats: FrameStats,
 }
 
 impl TimeState {
     pub fn new() -> Self {
+        Self::with_target(FrameStats::DEFAULT_TARGET)
+    }
+
+    /// Creates a time state for a loop aiming to run once every `target`.
+    pub fn with_target(target: Duration) -> Self {
         let now = Instant::now();
         Self {
             start: now,
             last_update: now,
             elapsed: Default::default(),
             last_update_time: Default::default(),
+            stats: FrameStats::new(target),
         }
     }
 
@@ -23,6 +31,7 @@ impl TimeState {
         self.last_update_time = now.duration_since(self.last_update);
         self.last_update = now;
         self.elapsed = now.duration_since(self.start);
+        self.stats.record(self.last_update_time);
     }
 
     pub fn start(&self) -> Instant {
@@ -40,6 +49,14 @@ impl TimeState {
     pub fn elapsed(&self) -> Duration {
         self.elapsed
     }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    pub fn stats_mut(&mut self) -> &mut FrameStats {
+        &mut self.stats
+    }
 }
 
 impl Default for TimeState {
@@ -47,3 +64,162 @@ impl Default for TimeState {
         Self::new()
     }
 }
+
+/// Rolling statistics over the most recent frame times.
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    frames: VecDeque<Duration>,
+    capacity: usize,
+    target: Duration,
+    total_frames: u64,
+    total_over_budget: u64,
+}
+
+impl FrameStats {
+    /// How many frame times are kept by default.
+    pub const DEFAULT_CAPACITY: usize = 240;
+
+    /// The default target frame time, 60 frames per second.
+    pub const DEFAULT_TARGET: Duration = Duration::from_micros(16_667);
+
+    pub fn new(target: Duration) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
+            capacity: Self::DEFAULT_CAPACITY,
+            target,
+            total_frames: 0,
+            total_over_budget: 0,
+        }
+    }
+
+    /// Records a frame time, forgetting the oldest one if full.
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_time);
+        self.total_frames += 1;
+        self.total_over_budget += self.is_over_budget(frame_time) as u64;
+    }
+
+    /// Forgets all recorded frame times, including the totals.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.total_frames = 0;
+        self.total_over_budget = 0;
+    }
+
+    /// How many frame times are kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.frames.len() > self.capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    /// The frame time the loop aims for.
+    pub fn target(&self) -> Duration {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Duration) {
+        self.target = target;
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The recorded frame times, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.frames.iter().copied()
+    }
+
+    /// The most recent frame time.
+    pub fn last(&self) -> Option<Duration> {
+        self.frames.back().copied()
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::default();
+        }
+        self.frames.iter().sum::<Duration>() / self.frames.len() as u32
+    }
+
+    /// Frames per second, going by the average frame time.
+    pub fn fps(&self) -> f64 {
+        let average = self.average().as_secs_f64();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.frames.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.frames.iter().max().copied().unwrap_or_default()
+    }
+
+    /// The frame time below which the given fraction of frames fall, with
+    /// `fraction` between 0 and 1. For example, `percentile(0.99)` is the
+    /// 99th percentile.
+    pub fn percentile(&self, fraction: f64) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::default();
+        }
+        let mut sorted: Vec<Duration> = self.frames.iter().copied().collect();
+        sorted.sort_unstable();
+        let fraction = fraction.max(0.0).min(1.0);
+        let i = ((sorted.len() - 1) as f64 * fraction).round() as usize;
+        sorted[i]
+    }
+
+    /// How many of the recorded frames went over budget, as told by
+    /// [FrameStats::is_over_budget].
+    ///
+    /// The target is often a cap rather than the display's refresh interval,
+    /// so this doesn't tell how many frames the display actually missed.
+    pub fn over_budget(&self) -> u64 {
+        self.frames
+            .iter()
+            .filter(|&&d| self.is_over_budget(d))
+            .count() as u64
+    }
+
+    /// How many frames were recorded since creation or the last clear.
+    pub fn total_frames(&self) -> u64 {
+        self.total_frames
+    }
+
+    /// How many frames went over budget since creation or the last clear.
+    pub fn total_over_budget(&self) -> u64 {
+        self.total_over_budget
+    }
+
+    /// The longest a frame can take without going over budget: half as long
+    /// again as the target. The loop only redraws once the target has
+    /// passed, so nearly every frame runs a little past it, and only frames
+    /// this late mean one was missed.
+    pub fn budget(&self) -> Duration {
+        self.target * 3 / 2
+    }
+
+    /// Whether a frame taking `frame_time` went over [FrameStats::budget].
+    /// Nothing is over budget without a target.
+    pub fn is_over_budget(&self, frame_time: Duration) -> bool {
+        self.target != Duration::default() && frame_time > self.budget()
+    }
+}
@@ -10,7 +10,8 @@ pub mod prelude {
         ResourceUser,
     };
     pub use crate::framework::widgets::{
-        LayoutDimension, LayoutSize, Widget, WidgetState, Wrap, Wrappable,
+        LayoutDimension, LayoutSize, Widget, WidgetNode, WidgetState, Wrap,
+        Wrappable,
     };
     pub use crate::framework::FrameworkState;
     pub use crate::game::{InputEvent, ScrollAmount, State};
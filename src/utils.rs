@@ -2,6 +2,7 @@ mod bottom_right;
 mod center;
 mod clamp_unit;
 mod easing;
+mod lerp;
 mod paint;
 mod snap;
 mod to_26dot6;
@@ -10,7 +11,8 @@ mod vector;
 pub use bottom_right::BottomRight;
 pub use center::Center;
 pub use clamp_unit::ClampUnit;
-pub use easing::Easing;
+pub use easing::{Easing, EasingFn};
+pub use lerp::Lerp;
 pub use paint::PaintUtils;
 pub use snap::Snap;
 pub use to_26dot6::To26Dot6;
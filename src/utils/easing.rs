@@ -1,5 +1,10 @@
+use std::f32::consts::PI;
+
 use crate::skia::{scalar, Vector};
 
+/// How far back curves overshoot, giving about 10%.
+const BACK: scalar = 1.70158;
+
 pub trait Easing
 where
     Self: Sized,
@@ -12,6 +17,36 @@ where
     fn ease_in_out_sine(self) -> Self;
     fn ease_out_sine(self) -> Self;
 
+    fn ease_in_expo(self) -> Self;
+    fn ease_in_out_expo(self) -> Self;
+    fn ease_out_expo(self) -> Self;
+
+    fn ease_in_circ(self) -> Self;
+    fn ease_in_out_circ(self) -> Self;
+    fn ease_out_circ(self) -> Self;
+
+    fn ease_in_back(self) -> Self;
+    fn ease_in_out_back(self) -> Self;
+    fn ease_out_back(self) -> Self;
+
+    fn ease_in_elastic(self) -> Self;
+    fn ease_in_out_elastic(self) -> Self;
+    fn ease_out_elastic(self) -> Self;
+
+    fn ease_in_bounce(self) -> Self;
+    fn ease_in_out_bounce(self) -> Self;
+    fn ease_out_bounce(self) -> Self;
+
+    /// A CSS-style cubic Bézier timing curve through `(0, 0)`, `(x1, y1)`,
+    /// `(x2, y2)` and `(1, 1)`, like `cubic-bezier()` in CSS.
+    fn cubic_bezier(
+        self,
+        x1: scalar,
+        y1: scalar,
+        x2: scalar,
+        y2: scalar,
+    ) -> Self;
+
     #[inline(always)]
     fn ease_in_quad(self) -> Self {
         self.ease_in_pow(2)
@@ -107,6 +142,152 @@ impl Easing for scalar {
     fn ease_out_sine(self) -> Self {
         (self * std::f32::consts::FRAC_PI_2).sin()
     }
+
+    #[inline(always)]
+    fn ease_in_expo(self) -> Self {
+        if self <= 0.0 {
+            0.0
+        } else {
+            (2f32).powf(10.0 * self - 10.0)
+        }
+    }
+
+    #[inline(always)]
+    fn ease_in_out_expo(self) -> Self {
+        if self <= 0.0 {
+            0.0
+        } else if self >= 1.0 {
+            1.0
+        } else if self < 0.5 {
+            (2f32).powf(20.0 * self - 10.0) * 0.5
+        } else {
+            (2.0 - (2f32).powf(-20.0 * self + 10.0)) * 0.5
+        }
+    }
+
+    #[inline(always)]
+    fn ease_out_expo(self) -> Self {
+        if self >= 1.0 {
+            1.0
+        } else {
+            1.0 - (2f32).powf(-10.0 * self)
+        }
+    }
+
+    #[inline(always)]
+    fn ease_in_circ(self) -> Self {
+        1.0 - (1.0 - self * self).max(0.0).sqrt()
+    }
+
+    #[inline(always)]
+    fn ease_in_out_circ(self) -> Self {
+        if self < 0.5 {
+            (1.0 - (1.0 - (2.0 * self).powi(2)).max(0.0).sqrt()) * 0.5
+        } else {
+            ((1.0 - (-2.0 * self + 2.0).powi(2)).max(0.0).sqrt() + 1.0) * 0.5
+        }
+    }
+
+    #[inline(always)]
+    fn ease_out_circ(self) -> Self {
+        (1.0 - (self - 1.0).powi(2)).max(0.0).sqrt()
+    }
+
+    #[inline(always)]
+    fn ease_in_back(self) -> Self {
+        (BACK + 1.0) * self.powi(3) - BACK * self * self
+    }
+
+    #[inline(always)]
+    fn ease_in_out_back(self) -> Self {
+        let c = BACK * 1.525;
+        if self < 0.5 {
+            (2.0 * self).powi(2) * ((c + 1.0) * 2.0 * self - c) * 0.5
+        } else {
+            let t = 2.0 * self - 2.0;
+            (t * t * ((c + 1.0) * t + c) + 2.0) * 0.5
+        }
+    }
+
+    #[inline(always)]
+    fn ease_out_back(self) -> Self {
+        let t = self - 1.0;
+        1.0 + (BACK + 1.0) * t.powi(3) + BACK * t * t
+    }
+
+    #[inline(always)]
+    fn ease_in_elastic(self) -> Self {
+        if self <= 0.0 || self >= 1.0 {
+            return self.clamp(0.0, 1.0);
+        }
+        let c = 2.0 * PI / 3.0;
+        -(2f32).powf(10.0 * self - 10.0) * ((self * 10.0 - 10.75) * c).sin()
+    }
+
+    #[inline(always)]
+    fn ease_in_out_elastic(self) -> Self {
+        if self <= 0.0 || self >= 1.0 {
+            return self.clamp(0.0, 1.0);
+        }
+        let c = 2.0 * PI / 4.5;
+        let s = ((20.0 * self - 11.125) * c).sin();
+        if self < 0.5 {
+            -((2f32).powf(20.0 * self - 10.0) * s) * 0.5
+        } else {
+            (2f32).powf(-20.0 * self + 10.0) * s * 0.5 + 1.0
+        }
+    }
+
+    #[inline(always)]
+    fn ease_out_elastic(self) -> Self {
+        if self <= 0.0 || self >= 1.0 {
+            return self.clamp(0.0, 1.0);
+        }
+        let c = 2.0 * PI / 3.0;
+        (2f32).powf(-10.0 * self) * ((self * 10.0 - 0.75) * c).sin() + 1.0
+    }
+
+    #[inline(always)]
+    fn ease_in_bounce(self) -> Self {
+        1.0 - (1.0 - self).ease_out_bounce()
+    }
+
+    #[inline(always)]
+    fn ease_in_out_bounce(self) -> Self {
+        if self < 0.5 {
+            (1.0 - (1.0 - 2.0 * self).ease_out_bounce()) * 0.5
+        } else {
+            (1.0 + (2.0 * self - 1.0).ease_out_bounce()) * 0.5
+        }
+    }
+
+    #[inline(always)]
+    fn ease_out_bounce(self) -> Self {
+        const N: scalar = 7.5625;
+        const D: scalar = 2.75;
+        if self < 1.0 / D {
+            N * self * self
+        } else if self < 2.0 / D {
+            let t = self - 1.5 / D;
+            N * t * t + 0.75
+        } else if self < 2.5 / D {
+            let t = self - 2.25 / D;
+            N * t * t + 0.9375
+        } else {
+            let t = self - 2.625 / D;
+            N * t * t + 0.984375
+        }
+    }
+
+    fn cubic_bezier(
+        self,
+        x1: scalar,
+        y1: scalar,
+        x2: scalar,
+        y2: scalar,
+    ) -> Self {
+        cubic_bezier(self, x1, y1, x2, y2)
+    }
 }
 
 impl Easing for Vector {
@@ -133,4 +314,214 @@ impl Easing for Vector {
     fn ease_out_sine(self) -> Self {
         Self::new(self.x.ease_out_sine(), self.y.ease_out_sine())
     }
+
+    fn ease_in_expo(self) -> Self {
+        Self::new(self.x.ease_in_expo(), self.y.ease_in_expo())
+    }
+
+    fn ease_in_out_expo(self) -> Self {
+        Self::new(self.x.ease_in_out_expo(), self.y.ease_in_out_expo())
+    }
+
+    fn ease_out_expo(self) -> Self {
+        Self::new(self.x.ease_out_expo(), self.y.ease_out_expo())
+    }
+
+    fn ease_in_circ(self) -> Self {
+        Self::new(self.x.ease_in_circ(), self.y.ease_in_circ())
+    }
+
+    fn ease_in_out_circ(self) -> Self {
+        Self::new(self.x.ease_in_out_circ(), self.y.ease_in_out_circ())
+    }
+
+    fn ease_out_circ(self) -> Self {
+        Self::new(self.x.ease_out_circ(), self.y.ease_out_circ())
+    }
+
+    fn ease_in_back(self) -> Self {
+        Self::new(self.x.ease_in_back(), self.y.ease_in_back())
+    }
+
+    fn ease_in_out_back(self) -> Self {
+        Self::new(self.x.ease_in_out_back(), self.y.ease_in_out_back())
+    }
+
+    fn ease_out_back(self) -> Self {
+        Self::new(self.x.ease_out_back(), self.y.ease_out_back())
+    }
+
+    fn ease_in_elastic(self) -> Self {
+        Self::new(self.x.ease_in_elastic(), self.y.ease_in_elastic())
+    }
+
+    fn ease_in_out_elastic(self) -> Self {
+        Self::new(self.x.ease_in_out_elastic(), self.y.ease_in_out_elastic())
+    }
+
+    fn ease_out_elastic(self) -> Self {
+        Self::new(self.x.ease_out_elastic(), self.y.ease_out_elastic())
+    }
+
+    fn ease_in_bounce(self) -> Self {
+        Self::new(self.x.ease_in_bounce(), self.y.ease_in_bounce())
+    }
+
+    fn ease_in_out_bounce(self) -> Self {
+        Self::new(self.x.ease_in_out_bounce(), self.y.ease_in_out_bounce())
+    }
+
+    fn ease_out_bounce(self) -> Self {
+        Self::new(self.x.ease_out_bounce(), self.y.ease_out_bounce())
+    }
+
+    fn cubic_bezier(
+        self,
+        x1: scalar,
+        y1: scalar,
+        x2: scalar,
+        y2: scalar,
+    ) -> Self {
+        Self::new(
+            self.x.cubic_bezier(x1, y1, x2, y2),
+            self.y.cubic_bezier(x1, y1, x2, y2),
+        )
+    }
+}
+
+/// An easing curve as plain data, so it can be stored and, with the `serde`
+/// feature, serialized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EasingFn {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InQuint,
+    OutQuint,
+    InOutQuint,
+    InSine,
+    OutSine,
+    InOutSine,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InCirc,
+    OutCirc,
+    InOutCirc,
+    InBack,
+    OutBack,
+    InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+    /// A CSS-style cubic Bézier curve through `(0, 0)`, `(x1, y1)`,
+    /// `(x2, y2)` and `(1, 1)`.
+    CubicBezier(scalar, scalar, scalar, scalar),
+}
+
+impl EasingFn {
+    /// Eases `t`, which goes from 0 to 1.
+    pub fn apply(self, t: scalar) -> scalar {
+        match self {
+            EasingFn::Linear => t,
+            EasingFn::InQuad => t.ease_in_quad(),
+            EasingFn::OutQuad => t.ease_out_quad(),
+            EasingFn::InOutQuad => t.ease_in_out_quad(),
+            EasingFn::InCubic => t.ease_in_cubic(),
+            EasingFn::OutCubic => t.ease_out_cubic(),
+            EasingFn::InOutCubic => t.ease_in_out_cubic(),
+            EasingFn::InQuart => t.ease_in_quart(),
+            EasingFn::OutQuart => t.ease_out_quart(),
+            EasingFn::InOutQuart => t.ease_in_out_quart(),
+            EasingFn::InQuint => t.ease_in_quint(),
+            EasingFn::OutQuint => t.ease_out_quint(),
+            EasingFn::InOutQuint => t.ease_in_out_quint(),
+            EasingFn::InSine => t.ease_in_sine(),
+            EasingFn::OutSine => t.ease_out_sine(),
+            EasingFn::InOutSine => t.ease_in_out_sine(),
+            EasingFn::InExpo => t.ease_in_expo(),
+            EasingFn::OutExpo => t.ease_out_expo(),
+            EasingFn::InOutExpo => t.ease_in_out_expo(),
+            EasingFn::InCirc => t.ease_in_circ(),
+            EasingFn::OutCirc => t.ease_out_circ(),
+            EasingFn::InOutCirc => t.ease_in_out_circ(),
+            EasingFn::InBack => t.ease_in_back(),
+            EasingFn::OutBack => t.ease_out_back(),
+            EasingFn::InOutBack => t.ease_in_out_back(),
+            EasingFn::InElastic => t.ease_in_elastic(),
+            EasingFn::OutElastic => t.ease_out_elastic(),
+            EasingFn::InOutElastic => t.ease_in_out_elastic(),
+            EasingFn::InBounce => t.ease_in_bounce(),
+            EasingFn::OutBounce => t.ease_out_bounce(),
+            EasingFn::InOutBounce => t.ease_in_out_bounce(),
+            EasingFn::CubicBezier(x1, y1, x2, y2) => {
+                t.cubic_bezier(x1, y1, x2, y2)
+            }
+        }
+    }
+}
+
+impl Default for EasingFn {
+    fn default() -> Self {
+        EasingFn::Linear
+    }
+}
+
+/// Evaluates a CSS-style cubic Bézier timing curve at `x`, solving for the
+/// curve parameter with Newton's method and falling back to bisection.
+fn cubic_bezier(
+    x: scalar,
+    x1: scalar,
+    y1: scalar,
+    x2: scalar,
+    y2: scalar,
+) -> scalar {
+    const EPSILON: scalar = 1e-6;
+
+    // Polynomial coefficients, with the end points fixed at (0, 0) and (1, 1).
+    let cx = 3.0 * x1;
+    let bx = 3.0 * (x2 - x1) - cx;
+    let ax = 1.0 - cx - bx;
+    let cy = 3.0 * y1;
+    let by = 3.0 * (y2 - y1) - cy;
+    let ay = 1.0 - cy - by;
+    let sample_x = |t: scalar| ((ax * t + bx) * t + cx) * t;
+    let sample_y = |t: scalar| ((ay * t + by) * t + cy) * t;
+    let slope_x = |t: scalar| (3.0 * ax * t + 2.0 * bx) * t + cx;
+
+    let x = x.clamp(0.0, 1.0);
+    let mut t = x;
+    for _ in 0..8 {
+        let error = sample_x(t) - x;
+        if error.abs() < EPSILON {
+            return sample_y(t);
+        }
+        let slope = slope_x(t);
+        if slope.abs() < EPSILON {
+            break;
+        }
+        t -= error / slope;
+    }
+
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..32 {
+        t = (lo + hi) * 0.5;
+        if sample_x(t) < x {
+            lo = t;
+        } else {
+            hi = t;
+        }
+    }
+    sample_y(t)
 }
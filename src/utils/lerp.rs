@@ -0,0 +1,57 @@
+use crate::skia::{scalar, Color4f, Matrix, Size, Vector};
+
+/// Linear interpolation, as used by tweens.
+pub trait Lerp
+where
+    Self: Sized,
+{
+    /// Interpolates between `self` at `t = 0` and `to` at `t = 1`. `t` isn't
+    /// clamped, as some easings overshoot.
+    fn lerp(self, to: Self, t: scalar) -> Self;
+}
+
+impl Lerp for scalar {
+    #[inline(always)]
+    fn lerp(self, to: Self, t: scalar) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vector {
+    fn lerp(self, to: Self, t: scalar) -> Self {
+        Self::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(self, to: Self, t: scalar) -> Self {
+        Self::new(self.width.lerp(to.width, t), self.height.lerp(to.height, t))
+    }
+}
+
+impl Lerp for Color4f {
+    fn lerp(self, to: Self, t: scalar) -> Self {
+        Self::new(
+            self.r.lerp(to.r, t),
+            self.g.lerp(to.g, t),
+            self.b.lerp(to.b, t),
+            self.a.lerp(to.a, t),
+        )
+    }
+}
+
+impl Lerp for Matrix {
+    /// Interpolates each of the nine values separately. This is fine for
+    /// translations and scales, but rotations shrink on the way.
+    fn lerp(self, to: Self, t: scalar) -> Self {
+        let (mut a, mut b) = ([0.0; 9], [0.0; 9]);
+        self.get_9(&mut a);
+        to.get_9(&mut b);
+        for (a, b) in a.iter_mut().zip(&b) {
+            *a = a.lerp(*b, t);
+        }
+        let mut m = Matrix::default();
+        m.set_9(&a);
+        m
+    }
+}